@@ -0,0 +1,504 @@
+use super::primitive::*;
+use std::collections::VecDeque;
+use std::iter::Rev;
+
+/// Iterator over a run of siblings, from `front` to `back` inclusive.
+///
+/// Returned by `children`, `following_siblings` and friends.
+pub struct Siblings<'a, T: 'a> {
+    data: &'a forest<T>,
+    front: node,
+    back: node,
+}
+
+/// Iterator over the ancestors of a node, nearest first.
+///
+/// Iterating from the back goes from the top-level node down, which costs
+/// a walk up from the front for each step.
+pub struct Ancestors<'a, T: 'a> {
+    data: &'a forest<T>,
+    front: node,
+    back: node,
+}
+
+/// Iterator over a run of nodes in pre-order, from `front` to `back` inclusive.
+///
+/// Returned by `descendants` and `following`.
+pub struct PreOrderRange<'a, T: 'a> {
+    data: &'a forest<T>,
+    front: node,
+    back: node,
+}
+
+/// Iterator over the nodes that precede a node in pre-order, excluding its ancestors.
+///
+/// Nodes are returned nearest first, i.e. in reverse pre-order, and in pre-order from the back.
+pub struct Preceding<'a, T: 'a> {
+    data: &'a forest<T>,
+    /// The node whose ancestors are skipped.
+    origin: node,
+    front: node,
+    back: node,
+    /// The nearest ancestor of `origin` that the front hasn't passed yet.
+    front_ancestor: node,
+    /// The farthest ancestor of `origin` that the back hasn't passed yet.
+    back_ancestor: node,
+}
+
+/// Iterator over nodes in level-order (breadth-first), yielding `(depth, node)`.
+///
+/// Returned by `level_order` and `level_order_subtree`.
+pub struct LevelOrder<'a, T: 'a> {
+    data: &'a forest<T>,
+    /// The node to be returned next, along with its depth.
+    cur: Option<(usize, node)>,
+    /// Whether `cur` stands alone, i.e. its siblings are not to be visited.
+    single: bool,
+    /// The first nodes of the sibling runs still to be visited.
+    pending: VecDeque<(usize, node)>,
+}
+
+/// Iterator over the nodes at a given depth below a node, first to last.
+pub struct Level<'a, T: 'a> {
+    data: &'a forest<T>,
+    root: node,
+    depth: usize,
+    cur: node,
+}
+
+impl<T> forest<T> {
+    fn axis_idx(&self, n: node) -> usize {
+        self.live_idx(n).expect("invalid node")
+    }
+
+    /// Returns the index of the entry whose child links hold the sibling list of `idx`.
+    fn sibling_owner_idx(&self, idx: usize) -> usize {
+        self.raw().parent(idx).into_opt_idx().unwrap_or(HEADER_IDX)
+    }
+
+    /// Returns the children of `n`, first to last.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a live node of this forest. The same holds for all axis iterators.
+    pub fn children(&self, n: node) -> Siblings<'_, T> {
+        let idx = self.axis_idx(n);
+        Siblings::new(self, self.raw().child_first(idx), self.raw().child_last(idx))
+    }
+
+    /// Returns the children of `n`, last to first.
+    pub fn children_rev(&self, n: node) -> Rev<Siblings<'_, T>> {
+        self.children(n).rev()
+    }
+
+    /// Returns the ancestors of `n`, starting from its parent.
+    pub fn ancestors(&self, n: node) -> Ancestors<'_, T> {
+        let idx = self.axis_idx(n);
+        let front = self.raw().parent(idx);
+        let back = if front.is_invalid() { front } else { self.top_of(n) };
+        Ancestors { data: self, front, back }
+    }
+
+    /// Returns `n` followed by its ancestors.
+    pub fn ancestors_or_self(&self, n: node) -> Ancestors<'_, T> {
+        let _ = self.axis_idx(n);
+        Ancestors {
+            data: self,
+            front: n,
+            back: self.top_of(n),
+        }
+    }
+
+    /// Returns the siblings after `n`, nearest first.
+    pub fn following_siblings(&self, n: node) -> Siblings<'_, T> {
+        let idx = self.axis_idx(n);
+        let owner_idx = self.sibling_owner_idx(idx);
+        Siblings::new(self, self.raw().next(idx), self.raw().child_last(owner_idx))
+    }
+
+    /// Returns the siblings before `n`, nearest first.
+    pub fn preceding_siblings(&self, n: node) -> Rev<Siblings<'_, T>> {
+        let idx = self.axis_idx(n);
+        let owner_idx = self.sibling_owner_idx(idx);
+        Siblings::new(self, self.raw().child_first(owner_idx), self.raw().prev(idx)).rev()
+    }
+
+    /// Returns the descendants of `n` in pre-order, not including `n` itself.
+    pub fn descendants(&self, n: node) -> PreOrderRange<'_, T> {
+        let idx = self.axis_idx(n);
+        let front = self.raw().child_first(idx);
+        let back = if front.is_invalid() {
+            front
+        } else {
+            self.deepest_last(n)
+        };
+        PreOrderRange::new(self, front, back)
+    }
+
+    /// Returns the nodes after `n` in pre-order, not including its descendants.
+    pub fn following(&self, n: node) -> PreOrderRange<'_, T> {
+        let mut cur = self.axis_idx(n);
+        let front = loop {
+            let next = self.raw().next(cur);
+            if !next.is_invalid() {
+                break next;
+            }
+            match self.raw().parent(cur).into_opt_idx() {
+                Some(parent_idx) => cur = parent_idx,
+                None => break node::new_invalid(),
+            }
+        };
+        let back = if front.is_invalid() {
+            front
+        } else {
+            self.deepest_last(self.raw().top_last_entry())
+        };
+        PreOrderRange::new(self, front, back)
+    }
+
+    /// Returns the nodes before `n` in pre-order, not including its ancestors, nearest first.
+    pub fn preceding(&self, n: node) -> Preceding<'_, T> {
+        let idx = self.axis_idx(n);
+        let top = self.top_of(n);
+        let mut preceding = Preceding {
+            data: self,
+            origin: n,
+            front: node::new_invalid(),
+            back: node::new_invalid(),
+            front_ancestor: self.raw().parent(idx),
+            back_ancestor: if top == n { node::new_invalid() } else { top },
+        };
+        preceding.front = preceding.skip_front(self.pre_order_prev(n));
+        if !preceding.front.is_invalid() {
+            preceding.back = preceding.skip_back(self.raw().top_first_entry());
+        }
+        preceding
+    }
+
+    /// Returns all nodes in level-order, top-level nodes having depth 0.
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        let first = self.raw().top_first_entry();
+        LevelOrder {
+            data: self,
+            cur: first.into_opt_node().map(|first| (0, first)),
+            single: false,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Returns the subtree of `n` in level-order, `n` itself having depth 0.
+    pub fn level_order_subtree(&self, n: node) -> LevelOrder<'_, T> {
+        let _ = self.axis_idx(n);
+        LevelOrder {
+            data: self,
+            cur: Some((0, n)),
+            single: true,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Returns the nodes `depth` levels below `n`, first to last.
+    ///
+    /// Depth 0 yields `n` itself, depth 1 its children, and so on.
+    pub fn level(&self, n: node, depth: usize) -> Level<'_, T> {
+        let _ = self.axis_idx(n);
+        let mut level = Level {
+            data: self,
+            root: n,
+            depth,
+            cur: node::new_invalid(),
+        };
+        level.cur = level.seek(n, 0);
+        level
+    }
+
+    /// Returns the top-level ancestor-or-self of `n`.
+    fn top_of(&self, mut n: node) -> node {
+        loop {
+            let parent = self.raw().parent(n.into_opt_idx().unwrap());
+            if parent.is_invalid() {
+                return n;
+            }
+            n = parent;
+        }
+    }
+
+    /// Returns the child of `ancestor` on the way down to `n`.
+    fn child_towards(&self, ancestor: node, mut n: node) -> node {
+        loop {
+            let parent = self.raw().parent(n.into_opt_idx().unwrap());
+            if parent == ancestor {
+                return n;
+            }
+            n = parent;
+        }
+    }
+
+    /// Returns the last node in pre-order within the subtree of `n`.
+    fn deepest_last(&self, mut n: node) -> node {
+        loop {
+            let child = self.raw().child_last(n.into_opt_idx().unwrap());
+            if child.is_invalid() {
+                return n;
+            }
+            n = child;
+        }
+    }
+
+    fn pre_order_next(&self, n: node) -> node {
+        let mut idx = n.into_opt_idx().unwrap();
+        let child = self.raw().child_first(idx);
+        if !child.is_invalid() {
+            return child;
+        }
+        loop {
+            let next = self.raw().next(idx);
+            if !next.is_invalid() {
+                return next;
+            }
+            match self.raw().parent(idx).into_opt_idx() {
+                Some(parent_idx) => idx = parent_idx,
+                None => return node::new_invalid(),
+            }
+        }
+    }
+
+    fn pre_order_prev(&self, n: node) -> node {
+        let idx = n.into_opt_idx().unwrap();
+        let prev = self.raw().prev(idx);
+        if prev.is_invalid() {
+            self.raw().parent(idx)
+        } else {
+            self.deepest_last(prev)
+        }
+    }
+}
+
+impl<'a, T> Siblings<'a, T> {
+    fn new(data: &'a forest<T>, front: node, back: node) -> Self {
+        if front.is_invalid() || back.is_invalid() {
+            Siblings {
+                data,
+                front: node::new_invalid(),
+                back: node::new_invalid(),
+            }
+        } else {
+            Siblings { data, front, back }
+        }
+    }
+}
+
+impl<'a, T> Iterator for Siblings<'a, T> {
+    type Item = node;
+
+    fn next(&mut self) -> Option<node> {
+        let cur = self.front.into_opt_node()?;
+        if cur == self.back {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            self.front = self.data.raw().next(cur.into_opt_idx().unwrap());
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Siblings<'a, T> {
+    fn next_back(&mut self) -> Option<node> {
+        let cur = self.back.into_opt_node()?;
+        if cur == self.front {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            self.back = self.data.raw().prev(cur.into_opt_idx().unwrap());
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> Iterator for Ancestors<'a, T> {
+    type Item = node;
+
+    fn next(&mut self) -> Option<node> {
+        let cur = self.front.into_opt_node()?;
+        if cur == self.back {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            self.front = self.data.raw().parent(cur.into_opt_idx().unwrap());
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Ancestors<'a, T> {
+    fn next_back(&mut self) -> Option<node> {
+        let cur = self.back.into_opt_node()?;
+        if cur == self.front {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            self.back = self.data.child_towards(cur, self.front);
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> PreOrderRange<'a, T> {
+    fn new(data: &'a forest<T>, front: node, back: node) -> Self {
+        PreOrderRange { data, front, back }
+    }
+}
+
+impl<'a, T> Iterator for PreOrderRange<'a, T> {
+    type Item = node;
+
+    fn next(&mut self) -> Option<node> {
+        let cur = self.front.into_opt_node()?;
+        if cur == self.back {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            self.front = self.data.pre_order_next(cur);
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for PreOrderRange<'a, T> {
+    fn next_back(&mut self) -> Option<node> {
+        let cur = self.back.into_opt_node()?;
+        if cur == self.front {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            self.back = self.data.pre_order_prev(cur);
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> Preceding<'a, T> {
+    /// Moves `pos` backward in pre-order past the ancestors of the origin.
+    fn skip_front(&mut self, mut pos: node) -> node {
+        while !pos.is_invalid() && pos == self.front_ancestor {
+            self.front_ancestor = self.data.raw().parent(pos.into_opt_idx().unwrap());
+            pos = self.data.pre_order_prev(pos);
+        }
+        pos
+    }
+
+    /// Moves `pos` forward in pre-order past the ancestors of the origin.
+    fn skip_back(&mut self, mut pos: node) -> node {
+        while pos == self.back_ancestor {
+            self.back_ancestor = self.data.child_towards(pos, self.origin);
+            pos = self.data.pre_order_next(pos);
+        }
+        pos
+    }
+}
+
+impl<'a, T> Iterator for Preceding<'a, T> {
+    type Item = node;
+
+    fn next(&mut self) -> Option<node> {
+        let cur = self.front.into_opt_node()?;
+        if cur == self.back {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            let prev = self.data.pre_order_prev(cur);
+            self.front = self.skip_front(prev);
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Preceding<'a, T> {
+    fn next_back(&mut self) -> Option<node> {
+        let cur = self.back.into_opt_node()?;
+        if cur == self.front {
+            self.front = node::new_invalid();
+            self.back = node::new_invalid();
+        } else {
+            let next = self.data.pre_order_next(cur);
+            self.back = self.skip_back(next);
+        }
+        Some(cur)
+    }
+}
+
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+    type Item = (usize, node);
+
+    fn next(&mut self) -> Option<(usize, node)> {
+        let (depth, cur) = self.cur.take().or_else(|| self.pending.pop_front())?;
+        let raw = self.data.raw();
+        let cur_idx = cur.into_opt_idx().unwrap();
+        let child = raw.child_first(cur_idx);
+        if !child.is_invalid() {
+            self.pending.push_back((depth + 1, child));
+        }
+        if self.single {
+            self.single = false;
+        } else {
+            self.cur = raw.next(cur_idx).into_opt_node().map(|next| (depth, next));
+        }
+        Some((depth, cur))
+    }
+}
+
+impl<'a, T> Level<'a, T> {
+    /// Returns the first node at the target depth in pre-order, starting from `pos` at depth `pos_depth`.
+    fn seek(&self, mut pos: node, mut pos_depth: usize) -> node {
+        let raw = self.data.raw();
+        loop {
+            if pos_depth == self.depth {
+                return pos;
+            }
+            let child = raw.child_first(pos.into_opt_idx().unwrap());
+            if !child.is_invalid() {
+                pos = child;
+                pos_depth += 1;
+                continue;
+            }
+            match self.skip_subtree_of(pos, pos_depth) {
+                Some((next, next_depth)) => {
+                    pos = next;
+                    pos_depth = next_depth;
+                }
+                None => return node::new_invalid(),
+            }
+        }
+    }
+
+    /// Returns the first node after the subtree of `pos` in pre-order, without leaving `root`.
+    fn skip_subtree_of(&self, mut pos: node, mut pos_depth: usize) -> Option<(node, usize)> {
+        let raw = self.data.raw();
+        loop {
+            if pos == self.root {
+                return None;
+            }
+            let pos_idx = pos.into_opt_idx().unwrap();
+            let next = raw.next(pos_idx);
+            if !next.is_invalid() {
+                return Some((next, pos_depth));
+            }
+            pos = raw.parent(pos_idx);
+            pos_depth -= 1;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Level<'a, T> {
+    type Item = node;
+
+    fn next(&mut self) -> Option<node> {
+        let cur = self.cur.into_opt_node()?;
+        self.cur = match self.skip_subtree_of(cur, self.depth) {
+            Some((next, next_depth)) => self.seek(next, next_depth),
+            None => node::new_invalid(),
+        };
+        Some(cur)
+    }
+}
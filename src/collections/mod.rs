@@ -1,6 +1,10 @@
+use super::error::*;
 use super::navigator::*;
 use super::primitive::*;
-use std::ops::{Deref, DerefMut};
+use std::iter;
+use std::mem;
+use std::ops::{Bound, Deref, DerefMut, RangeBounds};
+use std::sync::atomic::{AtomicU32, Ordering};
 
 macro_rules! intrinsics_assume {
     ($x:expr) => {}; //    ($x: expr) => {::std::intrinsics::assume($x);};
@@ -8,11 +12,21 @@ macro_rules! intrinsics_assume {
 
 /// A growable forest type.
 ///
-#[derive(Clone, Default)]
+/// Removed nodes leave vacant slots behind, which are reused by later insertions.
+///
+/// A forest created with `with_identity` rejects `node`s handed out by other forests.
+/// Clones share the identity of the original, since its handles address the same nodes there.
+#[derive(Clone)]
 pub struct Forest<T> {
     data: Vec<ForestEntry<T>>,
 }
 
+impl<T> Default for Forest<T> {
+    fn default() -> Self {
+        Forest::new()
+    }
+}
+
 impl<T> AsRef<forest<T>> for Forest<T> {
     fn as_ref(&self) -> &forest<T> {
         self.deref()
@@ -27,74 +41,139 @@ impl<T> AsMut<forest<T>> for Forest<T> {
 
 impl<T> Forest<T> {
     pub fn new() -> Self {
-        Forest { data: Vec::new() }
+        Forest::with_forest_id(ANONYMOUS_FOREST_ID)
+    }
+
+    /// Creates a forest with a unique identity.
+    ///
+    /// Its methods treat `node`s from any other forest as invalid instead of
+    /// addressing an unrelated entry.
+    pub fn with_identity() -> Self {
+        static NEXT_FOREST_ID: AtomicU32 = AtomicU32::new(ANONYMOUS_FOREST_ID + 1);
+        let forest_id = NEXT_FOREST_ID.fetch_add(1, Ordering::Relaxed);
+        assert!(forest_id != ANONYMOUS_FOREST_ID, "forest identities exhausted");
+        Forest::with_forest_id(forest_id)
+    }
+
+    fn with_forest_id(forest_id: u32) -> Self {
+        Forest {
+            data: vec![ForestEntry::new_header(forest_id)],
+        }
     }
 
     pub fn get_first_root_node(&self) -> Option<node> {
         self.seek_entry(SeekPos::TopFirst).into_opt_node()
     }
 
-    fn check_node_validity(&self, n: node) -> usize {
-        let len = self.len();
-        n.into_opt_idx()
-            .and_then(|x| if x < len { Some(x) } else { None })
-            .expect("node out of bound")
+    pub fn get_last_root_node(&self) -> Option<node> {
+        self.seek_entry(SeekPos::TopLast).into_opt_node()
+    }
+
+    pub(crate) fn node_idx(&self, n: node) -> Result<usize, ForestError> {
+        self.live_idx(n).ok_or(ForestError::InvalidNode)
+    }
+
+    /// Returns the entry of `n`, if it is a live node of this forest.
+    pub(crate) fn live_entry(&self, n: node) -> Result<&ForestEntry<T>, ForestError> {
+        Ok(&self.data[self.node_idx(n)?])
     }
 
     pub fn get_parent_node(&self, n: node) -> Option<node> {
-        let cur_idx = self.check_node_validity(n);
+        let cur_idx = self.live_idx(n)?;
         let cur_entry = self.data.get(cur_idx).unwrap();
         cur_entry.parent.into_opt_node()
     }
 
     pub fn get_prev_sibling_node(&self, n: node) -> Option<node> {
-        let cur_idx = self.check_node_validity(n);
+        let cur_idx = self.live_idx(n)?;
         let cur_entry = self.data.get(cur_idx).unwrap();
         cur_entry.prev.into_opt_node()
     }
 
     pub fn get_next_sibling_node(&self, n: node) -> Option<node> {
-        let cur_idx = self.check_node_validity(n);
+        let cur_idx = self.live_idx(n)?;
         let cur_entry = self.data.get(cur_idx).unwrap();
         cur_entry.next.into_opt_node()
     }
 
     pub fn get_first_child_node(&self, n: node) -> Option<node> {
-        let cur_idx = self.check_node_validity(n);
+        let cur_idx = self.live_idx(n)?;
         let cur_entry = self.data.get(cur_idx).unwrap();
         cur_entry.child_first.into_opt_node()
     }
 
     pub fn get_last_child_node(&self, n: node) -> Option<node> {
-        let cur_idx = self.check_node_validity(n);
+        let cur_idx = self.live_idx(n)?;
         let cur_entry = self.data.get(cur_idx).unwrap();
         cur_entry.child_last.into_opt_node()
     }
 
-    fn check_node_not_equal_or_ancestor_of_another(&self, a: node, b: node) -> bool {
-        let target_idx = self.check_node_validity(a);
-        let mut cur_idx = self.check_node_validity(b);
+    pub fn try_get_parent_node(&self, n: node) -> Result<Option<node>, ForestError> {
+        let cur_idx = self.node_idx(n)?;
+        Ok(self.data[cur_idx].parent.into_opt_node())
+    }
+
+    pub fn try_get_prev_sibling_node(&self, n: node) -> Result<Option<node>, ForestError> {
+        let cur_idx = self.node_idx(n)?;
+        Ok(self.data[cur_idx].prev.into_opt_node())
+    }
+
+    pub fn try_get_next_sibling_node(&self, n: node) -> Result<Option<node>, ForestError> {
+        let cur_idx = self.node_idx(n)?;
+        Ok(self.data[cur_idx].next.into_opt_node())
+    }
+
+    pub fn try_get_first_child_node(&self, n: node) -> Result<Option<node>, ForestError> {
+        let cur_idx = self.node_idx(n)?;
+        Ok(self.data[cur_idx].child_first.into_opt_node())
+    }
+
+    pub fn try_get_last_child_node(&self, n: node) -> Result<Option<node>, ForestError> {
+        let cur_idx = self.node_idx(n)?;
+        Ok(self.data[cur_idx].child_last.into_opt_node())
+    }
+
+    fn check_node_adoptable(&self, n: node, child: node) -> Result<(), ForestError> {
+        let target_idx = self.node_idx(child)?;
+        let mut cur_idx = self.node_idx(n)?;
         if cur_idx == target_idx {
-            return false;
+            return Err(ForestError::SameNode);
         }
         loop {
             let cur_entry = self.data.get(cur_idx).unwrap();
             if let Some(parent_idx) = cur_entry.parent.into_opt_idx() {
                 if parent_idx == target_idx {
-                    return false;
+                    return Err(ForestError::WouldCreateCycle);
                 } else {
                     cur_idx = parent_idx;
                     continue;
                 }
             } else {
-                return true;
+                return Ok(());
             }
         }
     }
 
+    fn check_node_referent(&self, n: node, child: node, referent: node) -> Result<(), ForestError> {
+        self.check_node_adoptable(n, child)?;
+        if self.try_get_parent_node(referent)? != Some(n) {
+            return Err(ForestError::ReferentNotChild);
+        }
+        if child == referent {
+            return Err(ForestError::SameNode);
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the entry that holds the first and last links of
+    /// the sibling list `cur_idx` is in. For top-level nodes, that is the header.
+    fn sibling_list_owner_idx(&self, cur_idx: usize) -> usize {
+        self.data[cur_idx].parent.into_opt_idx().unwrap_or(HEADER_IDX)
+    }
+
     fn reconnect_prev_next(&mut self, new_prev: node, cur: node, new_next: node) {
+        let cur_idx = cur.into_opt_idx().unwrap();
         {
-            let cur_idx = cur.into_opt_idx().unwrap();
             let cur_entry = self.data.get_mut(cur_idx).unwrap();
             cur_entry.prev = new_prev;
             cur_entry.next = new_next;
@@ -107,39 +186,82 @@ impl<T> Forest<T> {
             let next_entry = self.data.get_mut(next_idx).unwrap();
             next_entry.prev = cur;
         }
+        let owner_idx = self.sibling_list_owner_idx(cur_idx);
+        let owner_entry = self.data.get_mut(owner_idx).unwrap();
+        if new_prev.is_invalid() {
+            owner_entry.child_first = cur;
+        }
+        if new_next.is_invalid() {
+            owner_entry.child_last = cur;
+        }
+    }
+
+    fn adjust_len(&mut self, added: bool) {
+        if let EntryData::Header { ref mut len, .. } = self.data[HEADER_IDX].data {
+            if added {
+                *len += 1;
+            } else {
+                *len -= 1;
+            }
+        }
+    }
+
+    fn allocate_slot(&mut self, t: T) -> node {
+        let mut new_entry = ForestEntry::new(t);
+        self.adjust_len(true);
+        if let Some(free_idx) = self.data[HEADER_IDX].next.into_opt_idx() {
+            let next_free = self.data[free_idx].next;
+            self.data[HEADER_IDX].next = next_free;
+            new_entry.generation = self.data[free_idx].generation;
+            self.data[free_idx] = new_entry;
+            self.node_at(free_idx)
+        } else {
+            let new_idx = self.data.len();
+            self.data.push(new_entry);
+            self.node_at(new_idx)
+        }
+    }
+
+    /// Vacates the slot at `idx` and returns its value.
+    ///
+    /// A slot whose generation runs out is retired rather than reused,
+    /// so that old handles to it never become valid again.
+    fn release_slot(&mut self, idx: usize) -> T {
+        self.adjust_len(false);
+        let mut vacant_entry = ForestEntry::new_vacant();
+        // live slots never reach the last generation, see below.
+        vacant_entry.generation = self.data[idx].generation + 1;
+        let retired = vacant_entry.generation == u32::MAX;
+        if !retired {
+            vacant_entry.next = self.data[HEADER_IDX].next;
+        }
+        let old_entry = mem::replace(&mut self.data[idx], vacant_entry);
+        if !retired {
+            self.data[HEADER_IDX].next = self.node_at(idx);
+        }
+        match old_entry.data {
+            EntryData::Occupied(v) => v,
+            _ => unreachable!(),
+        }
     }
 
     fn prepare_new_node_at_top_last(&mut self, t: T) -> node {
         let top_last_node = self.seek_entry(SeekPos::TopLast);
-        let new_entry = ForestEntry::new(t);
-        let new_idx = self.data.len();
-        self.data.push(new_entry);
-        let new_node = unsafe { node::new_with_idx(new_idx) };
+        let new_node = self.allocate_slot(t);
         self.reconnect_prev_next(top_last_node, new_node, node::new_invalid());
         new_node
     }
 
-    fn disconnect_node_from_parent(&mut self, cur: node, parent: node) {
+    /// Clears the parent link of `cur`, which must have been knocked out of its siblings.
+    fn disconnect_node_from_parent(&mut self, cur: node) {
         let cur_idx = cur.into_opt_idx().unwrap();
-        let parent_idx = parent.into_opt_idx().unwrap();
-
-        let (cur_prev, cur_next) = {
-            let cur_entry = self.data.get_mut(cur_idx).unwrap();
-            cur_entry.parent = node::new_invalid();
-            (cur_entry.prev, cur_entry.next)
-        };
-
-        let parent_entry = self.data.get_mut(parent_idx).unwrap();
-        if parent_entry.child_first == cur {
-            parent_entry.child_first = cur_next;
-        }
-        if parent_entry.child_last == cur {
-            parent_entry.child_last = cur_prev;
-        }
+        let cur_entry = self.data.get_mut(cur_idx).unwrap();
+        cur_entry.parent = node::new_invalid();
     }
 
     fn knockout_node_from_siblings(&mut self, cur: node) {
         let cur_idx = cur.into_opt_idx().unwrap();
+        let owner_idx = self.sibling_list_owner_idx(cur_idx);
         let (cur_prev, cur_next) = {
             let cur_entry = self.data.get_mut(cur_idx).unwrap();
             let pair = (cur_entry.prev, cur_entry.next);
@@ -155,6 +277,13 @@ impl<T> Forest<T> {
             let next_entry = self.data.get_mut(cur_next_idx).unwrap();
             next_entry.prev = cur_prev;
         }
+        let owner_entry = self.data.get_mut(owner_idx).unwrap();
+        if owner_entry.child_first == cur {
+            owner_entry.child_first = cur_next;
+        }
+        if owner_entry.child_last == cur {
+            owner_entry.child_last = cur_prev;
+        }
     }
 
     fn move_detached_node(&mut self, cur: node, new_parent: node, new_prev: node, new_next: node) {
@@ -163,31 +292,34 @@ impl<T> Forest<T> {
             let cur_entry = self.data.get_mut(cur_idx).unwrap();
             cur_entry.parent = new_parent;
         }
-        let parent_idx = new_parent.into_opt_idx().unwrap();
-        {
-            let parent_entry = self.data.get_mut(parent_idx).unwrap();
-            if parent_entry.child_first == new_next {
-                parent_entry.child_first = cur;
-            }
-            if parent_entry.child_last == new_prev {
-                parent_entry.child_last = cur;
-            }
-        }
         self.reconnect_prev_next(new_prev, cur, new_next);
     }
 
+    /// Creates a node as the last child of the live node `parent`, or as the last
+    /// top-level node if `parent` is invalid.
+    pub(crate) fn create_child_node(&mut self, parent: node, t: T) -> node {
+        let parent_idx = match parent.into_opt_idx() {
+            Some(parent_idx) => parent_idx,
+            None => return self.prepare_new_node_at_top_last(t),
+        };
+        let new_prev = self.data[parent_idx].child_last;
+        let new_node = self.allocate_slot(t);
+        self.move_detached_node(new_node, parent, new_prev, node::new_invalid());
+        new_node
+    }
+
     pub fn create_node(&mut self, t: T) -> node {
         self.prepare_new_node_at_top_last(t)
     }
 
     pub fn detach_node(&mut self, n: node) -> bool {
-        // check_node_validity will execute inside get_parent_node()
-        if let Some(parent_node) = self.get_parent_node(n) {
+        // live_idx will execute inside get_parent_node()
+        if self.get_parent_node(n).is_some() {
             // checkpoint, n is not toplevel
-            let top_last_node = self.seek_entry(SeekPos::TopLast);
-
-            self.disconnect_node_from_parent(n, parent_node);
             self.knockout_node_from_siblings(n);
+            self.disconnect_node_from_parent(n);
+
+            let top_last_node = self.seek_entry(SeekPos::TopLast);
             self.reconnect_prev_next(top_last_node, n, node::new_invalid());
 
             true
@@ -196,64 +328,418 @@ impl<T> Forest<T> {
         }
     }
 
-    pub fn prepend_node_child(&mut self, n: node, child: node) -> bool {
-        if self.check_node_not_equal_or_ancestor_of_another(child, n) {
+    /// Removes a node from the forest and returns its value.
+    ///
+    /// The children of the removed node become top-level nodes, appended in order.
+    /// Returns `None` if `n` has already been removed.
+    pub fn remove_node(&mut self, n: node) -> Option<T> {
+        let cur_idx = self.live_idx(n)?;
+        while let Some(child) = self.get_first_child_node(n) {
             let _ = self.detach_node(child);
-            let new_prev = node::new_invalid();
-            let new_next = self.get_first_child_node(n)
-                .unwrap_or_else(node::new_invalid);
-            self.knockout_node_from_siblings(child);
-            self.move_detached_node(child, n, new_prev, new_next);
-            true
-        } else {
-            false
         }
+        self.knockout_node_from_siblings(n);
+        self.disconnect_node_from_parent(n);
+        Some(self.release_slot(cur_idx))
     }
 
-    pub fn append_node_child(&mut self, n: node, child: node) -> bool {
-        if self.check_node_not_equal_or_ancestor_of_another(child, n) {
-            let _ = self.detach_node(child);
-            let new_next = node::new_invalid();
-            let new_prev = self.get_last_child_node(n)
-                .unwrap_or_else(node::new_invalid);
-            self.knockout_node_from_siblings(child);
-            self.move_detached_node(child, n, new_prev, new_next);
-            true
-        } else {
-            false
+    /// Removes a node together with all its descendants, dropping their values.
+    ///
+    /// Returns `false` if `n` has already been removed.
+    pub fn remove_subtree(&mut self, n: node) -> bool {
+        self.take_subtree_root(n).is_some()
+    }
+
+    /// Removes `n` together with all its descendants, dropping their values,
+    /// and returns the value of `n`.
+    ///
+    /// Returns `None` if `n` has already been removed.
+    pub(crate) fn take_subtree_root(&mut self, n: node) -> Option<T> {
+        let cur_idx = self.live_idx(n)?;
+        self.knockout_node_from_siblings(n);
+        self.disconnect_node_from_parent(n);
+
+        let mut root_value = None;
+        let mut pending = vec![cur_idx];
+        while let Some(idx) = pending.pop() {
+            let mut child = self.data[idx].child_first;
+            while let Some(child_idx) = child.into_opt_idx() {
+                pending.push(child_idx);
+                child = self.data[child_idx].next;
+            }
+            let v = self.release_slot(idx);
+            if idx == cur_idx {
+                root_value = Some(v);
+            }
         }
+        root_value
     }
 
-    pub fn insert_node_child_before(&mut self, n: node, child: node, referent: node) -> bool {
-        if self.check_node_not_equal_or_ancestor_of_another(child, n)
-            && self.get_parent_node(referent) == Some(n) && child != referent
-        {
-            let _ = self.detach_node(child);
-            let new_next = referent;
-            let new_prev = self.get_prev_sibling_node(referent)
-                .unwrap_or_else(node::new_invalid);
-            self.knockout_node_from_siblings(child);
-            self.move_detached_node(child, n, new_prev, new_next);
-            true
-        } else {
-            false
+    /// Removes `n` together with all its descendants, and returns them in pre-order
+    /// along with their parents and values. The parent of `n` itself is left out.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a live node of this forest.
+    pub(crate) fn take_subtree(&mut self, n: node) -> Vec<(node, Option<node>, T)> {
+        let order: Vec<_> = iter::once((n, None))
+            .chain(self.descendants(n).map(|cur| {
+                let parent = self.data[cur.into_opt_idx().unwrap()].parent;
+                (cur, parent.into_opt_node())
+            }))
+            .collect();
+        self.knockout_node_from_siblings(n);
+        self.disconnect_node_from_parent(n);
+        order
+            .into_iter()
+            .map(|(cur, parent)| (cur, parent, self.release_slot(cur.into_opt_idx().unwrap())))
+            .collect()
+    }
+
+    /// Moves all nodes of `fragment` to the end of the storage, renumbering their links in bulk,
+    /// and links its top-level nodes between `new_prev` and `new_next` under `parent`.
+    ///
+    /// An invalid `parent` means the top level. Returns the new handles of the fragment's
+    /// top-level nodes.
+    pub(crate) fn graft_forest(
+        &mut self,
+        parent: node,
+        new_prev: node,
+        new_next: node,
+        fragment: Forest<T>,
+    ) -> Vec<node> {
+        let base = self.data.len();
+        // maps the indices of the fragment to the new ones, with `HEADER_IDX` for unused slots.
+        let mut new_idxs = vec![HEADER_IDX; fragment.data.len()];
+        let mut count = 0;
+        for (old_idx, entry) in fragment.data.iter().enumerate() {
+            if old_idx != HEADER_IDX && entry.is_occupied() {
+                new_idxs[old_idx] = base + count;
+                count += 1;
+            }
         }
+        if count == 0 {
+            return Vec::new();
+        }
+        let (first_root, last_root) = {
+            let header = &fragment.data[HEADER_IDX];
+            (header.child_first, header.child_last)
+        };
+        self.data.extend(
+            fragment
+                .data
+                .into_iter()
+                .enumerate()
+                .filter(|&(old_idx, _)| new_idxs[old_idx] != HEADER_IDX)
+                .map(|(_, entry)| entry),
+        );
+
+        let remap = |data: &Self, n: node| match n.into_opt_idx() {
+            Some(old_idx) => data.node_at(new_idxs[old_idx]),
+            None => node::new_invalid(),
+        };
+        for idx in base..base + count {
+            let [parent_link, prev, next, child_first, child_last] = {
+                let entry = &self.data[idx];
+                [entry.parent, entry.prev, entry.next, entry.child_first, entry.child_last]
+            }.map(|link| remap(self, link));
+            let entry = &mut self.data[idx];
+            entry.parent = if parent_link.is_invalid() {
+                parent
+            } else {
+                parent_link
+            };
+            entry.prev = prev;
+            entry.next = next;
+            entry.child_first = child_first;
+            entry.child_last = child_last;
+        }
+
+        let first_root = remap(self, first_root);
+        let last_root = remap(self, last_root);
+        self.data[first_root.into_opt_idx().unwrap()].prev = new_prev;
+        self.data[last_root.into_opt_idx().unwrap()].next = new_next;
+        let owner_idx = parent.into_opt_idx().unwrap_or(HEADER_IDX);
+        match new_prev.into_opt_idx() {
+            Some(prev_idx) => self.data[prev_idx].next = first_root,
+            None => self.data[owner_idx].child_first = first_root,
+        }
+        match new_next.into_opt_idx() {
+            Some(next_idx) => self.data[next_idx].prev = last_root,
+            None => self.data[owner_idx].child_last = last_root,
+        }
+        if let EntryData::Header { ref mut len, .. } = self.data[HEADER_IDX].data {
+            *len += count;
+        }
+
+        let mut roots = vec![first_root];
+        let mut cur = first_root;
+        while cur != last_root {
+            cur = self.data[cur.into_opt_idx().unwrap()].next;
+            roots.push(cur);
+        }
+        roots
     }
 
-    pub fn insert_node_child_after(&mut self, n: node, child: node, referent: node) -> bool {
-        if self.check_node_not_equal_or_ancestor_of_another(child, n)
-            && self.get_parent_node(referent) == Some(n) && child != referent
+    /// Removes `n` and puts its children in its place, in order. Returns the value of `n`.
+    ///
+    /// Returns `None` if `n` has already been removed.
+    pub fn unwrap_node(&mut self, n: node) -> Option<T> {
+        let cur_idx = self.live_idx(n)?;
+        let (first, last) = (self.data[cur_idx].child_first, self.data[cur_idx].child_last);
+        if first.is_invalid() {
+            self.knockout_node_from_siblings(n);
+            self.disconnect_node_from_parent(n);
+            return Some(self.release_slot(cur_idx));
+        }
+        let (parent, prev, next) = {
+            let cur_entry = &self.data[cur_idx];
+            (cur_entry.parent, cur_entry.prev, cur_entry.next)
+        };
+        let mut child = first;
+        while let Some(child_idx) = child.into_opt_idx() {
+            self.data[child_idx].parent = parent;
+            child = self.data[child_idx].next;
+        }
+        self.data[first.into_opt_idx().unwrap()].prev = prev;
+        self.data[last.into_opt_idx().unwrap()].next = next;
+        let owner_idx = self.sibling_list_owner_idx(cur_idx);
+        match prev.into_opt_idx() {
+            Some(prev_idx) => self.data[prev_idx].next = first,
+            None => self.data[owner_idx].child_first = first,
+        }
+        match next.into_opt_idx() {
+            Some(next_idx) => self.data[next_idx].prev = last,
+            None => self.data[owner_idx].child_last = last,
+        }
+        Some(self.release_slot(cur_idx))
+    }
+
+    /// Creates a node in place of `n`, and moves `n` under it. Returns the new node.
+    ///
+    /// Returns `None` if `n` has already been removed.
+    pub fn wrap_node(&mut self, n: node, t: T) -> Option<node> {
+        let cur_idx = self.live_idx(n)?;
+        let (parent, prev, next) = {
+            let cur_entry = &self.data[cur_idx];
+            (cur_entry.parent, cur_entry.prev, cur_entry.next)
+        };
+        let new_node = self.allocate_slot(t);
         {
-            let _ = self.detach_node(child);
-            let new_prev = referent;
-            let new_next = self.get_next_sibling_node(referent)
-                .unwrap_or_else(node::new_invalid);
-            self.knockout_node_from_siblings(child);
-            self.move_detached_node(child, n, new_prev, new_next);
-            true
+            let new_entry = &mut self.data[new_node.into_opt_idx().unwrap()];
+            new_entry.parent = parent;
+            new_entry.child_first = n;
+            new_entry.child_last = n;
+        }
+        self.reconnect_prev_next(prev, new_node, next);
+        let cur_entry = &mut self.data[cur_idx];
+        cur_entry.parent = new_node;
+        cur_entry.prev = node::new_invalid();
+        cur_entry.next = node::new_invalid();
+        Some(new_node)
+    }
+
+    /// Creates a child of `n` in place of the children at positions `range`,
+    /// and moves those children under it. Returns the new node.
+    ///
+    /// An empty range inserts a new leaf at its start position.
+    /// Returns `None` if `n` has already been removed, or if `range` is out of bounds.
+    pub fn wrap_children<R>(&mut self, n: node, range: R, t: T) -> Option<node>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.live_idx(n)?;
+        let children: Vec<node> = self.children(n).collect();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => Some(start),
+            Bound::Excluded(&start) => start.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1),
+            Bound::Excluded(&end) => Some(end),
+            Bound::Unbounded => Some(children.len()),
+        };
+        let (start, end) = match (start, end) {
+            (Some(start), Some(end)) if start <= end && end <= children.len() => (start, end),
+            _ => return None,
+        };
+
+        let new_node = self.allocate_slot(t);
+        let new_idx = new_node.into_opt_idx().unwrap();
+        self.data[new_idx].parent = n;
+        let (prev, next) = if start == end {
+            let prev = if start > 0 {
+                children[start - 1]
+            } else {
+                node::new_invalid()
+            };
+            (prev, children.get(start).cloned().unwrap_or_else(node::new_invalid))
         } else {
-            false
+            let (first, last) = (children[start], children[end - 1]);
+            let first_idx = first.into_opt_idx().unwrap();
+            let last_idx = last.into_opt_idx().unwrap();
+            let pair = (self.data[first_idx].prev, self.data[last_idx].next);
+            self.data[first_idx].prev = node::new_invalid();
+            self.data[last_idx].next = node::new_invalid();
+            for child in &children[start..end] {
+                self.data[child.into_opt_idx().unwrap()].parent = new_node;
+            }
+            self.data[new_idx].child_first = first;
+            self.data[new_idx].child_last = last;
+            pair
+        };
+        self.reconnect_prev_next(prev, new_node, next);
+        Some(new_node)
+    }
+
+    pub fn prepend_node_child(&mut self, n: node, child: node) -> bool {
+        self.try_prepend_node_child(n, child).is_ok()
+    }
+
+    pub fn try_prepend_node_child(&mut self, n: node, child: node) -> Result<(), ForestError> {
+        self.check_node_adoptable(n, child)?;
+        let _ = self.detach_node(child);
+        let new_prev = node::new_invalid();
+        let new_next = self.get_first_child_node(n)
+            .unwrap_or_else(node::new_invalid);
+        self.knockout_node_from_siblings(child);
+        self.move_detached_node(child, n, new_prev, new_next);
+        Ok(())
+    }
+
+    pub fn append_node_child(&mut self, n: node, child: node) -> bool {
+        self.try_append_node_child(n, child).is_ok()
+    }
+
+    pub fn try_append_node_child(&mut self, n: node, child: node) -> Result<(), ForestError> {
+        self.check_node_adoptable(n, child)?;
+        let _ = self.detach_node(child);
+        let new_next = node::new_invalid();
+        let new_prev = self.get_last_child_node(n)
+            .unwrap_or_else(node::new_invalid);
+        self.knockout_node_from_siblings(child);
+        self.move_detached_node(child, n, new_prev, new_next);
+        Ok(())
+    }
+
+    pub fn insert_node_child_before(&mut self, n: node, child: node, referent: node) -> bool {
+        self.try_insert_node_child_before(n, child, referent).is_ok()
+    }
+
+    pub fn try_insert_node_child_before(
+        &mut self,
+        n: node,
+        child: node,
+        referent: node,
+    ) -> Result<(), ForestError> {
+        self.check_node_referent(n, child, referent)?;
+        let _ = self.detach_node(child);
+        let new_next = referent;
+        let new_prev = self.get_prev_sibling_node(referent)
+            .unwrap_or_else(node::new_invalid);
+        self.knockout_node_from_siblings(child);
+        self.move_detached_node(child, n, new_prev, new_next);
+        Ok(())
+    }
+
+    pub fn insert_node_child_after(&mut self, n: node, child: node, referent: node) -> bool {
+        self.try_insert_node_child_after(n, child, referent).is_ok()
+    }
+
+    pub fn try_insert_node_child_after(
+        &mut self,
+        n: node,
+        child: node,
+        referent: node,
+    ) -> Result<(), ForestError> {
+        self.check_node_referent(n, child, referent)?;
+        let _ = self.detach_node(child);
+        let new_prev = referent;
+        let new_next = self.get_next_sibling_node(referent)
+            .unwrap_or_else(node::new_invalid);
+        self.knockout_node_from_siblings(child);
+        self.move_detached_node(child, n, new_prev, new_next);
+        Ok(())
+    }
+
+    /// Moves `n` to the top level, right before the top-level node `referent`.
+    pub fn insert_root_before(&mut self, n: node, referent: node) -> bool {
+        self.try_insert_root_before(n, referent).is_ok()
+    }
+
+    pub fn try_insert_root_before(&mut self, n: node, referent: node) -> Result<(), ForestError> {
+        self.check_root_referent(n, referent)?;
+        let _ = self.detach_node(n);
+        self.knockout_node_from_siblings(n);
+        let new_prev = self.get_prev_sibling_node(referent)
+            .unwrap_or_else(node::new_invalid);
+        self.reconnect_prev_next(new_prev, n, referent);
+        Ok(())
+    }
+
+    /// Moves `n` to the top level, right after the top-level node `referent`.
+    pub fn insert_root_after(&mut self, n: node, referent: node) -> bool {
+        self.try_insert_root_after(n, referent).is_ok()
+    }
+
+    pub fn try_insert_root_after(&mut self, n: node, referent: node) -> Result<(), ForestError> {
+        self.check_root_referent(n, referent)?;
+        let _ = self.detach_node(n);
+        self.knockout_node_from_siblings(n);
+        let new_next = self.get_next_sibling_node(referent)
+            .unwrap_or_else(node::new_invalid);
+        self.reconnect_prev_next(referent, n, new_next);
+        Ok(())
+    }
+
+    /// Moves `n` to the top level, before all other top-level nodes.
+    pub fn prepend_root(&mut self, n: node) -> bool {
+        self.try_prepend_root(n).is_ok()
+    }
+
+    pub fn try_prepend_root(&mut self, n: node) -> Result<(), ForestError> {
+        self.try_move_to_root_at(n, 0)
+    }
+
+    /// Moves `n` to the top level, so that it becomes the top-level node at `position`.
+    ///
+    /// Positions past the last top-level node move `n` to the end.
+    pub fn move_to_root_at(&mut self, n: node, position: usize) -> bool {
+        self.try_move_to_root_at(n, position).is_ok()
+    }
+
+    pub fn try_move_to_root_at(&mut self, n: node, position: usize) -> Result<(), ForestError> {
+        let _ = self.node_idx(n)?;
+        let _ = self.detach_node(n);
+        let mut new_prev = node::new_invalid();
+        let mut new_next = self.seek_entry(SeekPos::TopFirst);
+        let mut remaining = position;
+        while let Some(cur) = new_next.into_opt_node() {
+            if cur == n {
+                new_next = self.data[cur.into_opt_idx().unwrap()].next;
+                continue;
+            }
+            if remaining == 0 {
+                break;
+            }
+            remaining -= 1;
+            new_prev = cur;
+            new_next = self.data[cur.into_opt_idx().unwrap()].next;
+        }
+        self.knockout_node_from_siblings(n);
+        self.reconnect_prev_next(new_prev, n, new_next);
+        Ok(())
+    }
+
+    fn check_root_referent(&self, n: node, referent: node) -> Result<(), ForestError> {
+        let _ = self.node_idx(n)?;
+        if self.try_get_parent_node(referent)?.is_some() {
+            return Err(ForestError::ReferentNotChild);
+        }
+        if n == referent {
+            return Err(ForestError::SameNode);
         }
+        Ok(())
     }
 }
 
@@ -0,0 +1,142 @@
+use super::collections::*;
+use super::navigator::*;
+use super::primitive::*;
+use std::mem;
+
+/// A cursor over a forest that can edit its structure in place.
+///
+/// The cursor always points to a live node. Edits keep it there, except `remove`,
+/// which moves it to a neighbour of the removed node.
+pub struct CursorMut<'a, T: 'a> {
+    forest: &'a mut Forest<T>,
+    cur: node,
+}
+
+impl<T> Forest<T> {
+    /// Returns a cursor pointing to `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a live node of this forest.
+    pub fn cursor_mut(&mut self, n: node) -> CursorMut<'_, T> {
+        assert!(self.get(n).is_some(), "invalid node");
+        CursorMut {
+            forest: self,
+            cur: n,
+        }
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the node the cursor points to.
+    pub fn node(&self) -> node {
+        self.cur
+    }
+
+    pub fn value(&self) -> &T {
+        &self.forest[self.cur]
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.forest[self.cur]
+    }
+
+    /// Returns the forest being edited.
+    pub fn forest(&self) -> &Forest<T> {
+        self.forest
+    }
+
+    /// Inserts a new first child, and returns it.
+    pub fn insert_child_first(&mut self, v: T) -> node {
+        let new_node = self.forest.create_node(v);
+        let inserted = self.forest.prepend_node_child(self.cur, new_node);
+        debug_assert!(inserted);
+        new_node
+    }
+
+    /// Inserts a new sibling right after the current node, and returns it.
+    pub fn insert_sibling_after(&mut self, v: T) -> node {
+        let new_node = self.forest.create_node(v);
+        let inserted = match self.forest.get_parent_node(self.cur) {
+            Some(parent) => self.forest.insert_node_child_after(parent, new_node, self.cur),
+            None => self.forest.insert_root_after(new_node, self.cur),
+        };
+        debug_assert!(inserted);
+        new_node
+    }
+
+    /// Inserts a new sibling right before the current node, and returns it.
+    pub fn insert_sibling_before(&mut self, v: T) -> node {
+        let new_node = self.forest.create_node(v);
+        let inserted = match self.forest.get_parent_node(self.cur) {
+            Some(parent) => self.forest.insert_node_child_before(parent, new_node, self.cur),
+            None => self.forest.insert_root_before(new_node, self.cur),
+        };
+        debug_assert!(inserted);
+        new_node
+    }
+
+    /// Puts a new node in place of the current one, and moves the current node under it.
+    ///
+    /// Returns the new parent.
+    pub fn wrap_in_parent(&mut self, v: T) -> node {
+        self.forest.wrap_node(self.cur, v).unwrap()
+    }
+
+    /// Moves the current node, with its descendants, to the end of the top level.
+    ///
+    /// Returns `false` if it already is a top-level node.
+    pub fn detach(&mut self) -> bool {
+        self.forest.detach_node(self.cur)
+    }
+
+    /// Replaces the value of the current node, and returns the old one.
+    pub fn replace(&mut self, v: T) -> T {
+        mem::replace(self.value_mut(), v)
+    }
+
+    /// Removes the current node with its descendants, and returns its value.
+    ///
+    /// The cursor moves to the next sibling, or else the previous sibling, or else the parent.
+    /// It is returned only if one of those exists.
+    pub fn remove(self) -> (T, Option<Self>) {
+        let CursorMut { forest, cur } = self;
+        let target = forest
+            .get_next_sibling_node(cur)
+            .or_else(|| forest.get_prev_sibling_node(cur))
+            .or_else(|| forest.get_parent_node(cur));
+        let v = forest.take_subtree_root(cur).unwrap();
+        let cursor = target.map(move |target| CursorMut {
+            forest,
+            cur: target,
+        });
+        (v, cursor)
+    }
+}
+
+impl<'a, T> Navigator for CursorMut<'a, T> {
+    type Item = node;
+
+    /// Moves to the parent, first child or a sibling, and returns it. Returns `None`
+    /// and stays put if there is none.
+    ///
+    /// The cursor has no entering or leaving side, so both `Up` directions move to the parent.
+    fn navigate(&mut self, dir: NavigateDir) -> Option<node> {
+        let target = match dir {
+            NavigateDir::Up(_) => self.forest.get_parent_node(self.cur),
+            NavigateDir::Down => self.forest.get_first_child_node(self.cur),
+            NavigateDir::Left => self.forest.get_prev_sibling_node(self.cur),
+            NavigateDir::Right => self.forest.get_next_sibling_node(self.cur),
+        }?;
+        self.cur = target;
+        Some(target)
+    }
+
+    /// Moves to the first or last top-level node, or down its first or last children
+    /// as far as they go, and returns it.
+    fn seek(&mut self, pos: SeekPos) -> Option<node> {
+        let target = self.forest.seek_entry(pos).into_opt_node()?;
+        self.cur = target;
+        Some(target)
+    }
+}
@@ -0,0 +1,32 @@
+use std::error::Error;
+use std::fmt;
+
+/// The reason a structural operation on a `Forest` was rejected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ForestError {
+    /// The `node` was removed, or belongs to another forest.
+    InvalidNode,
+    /// The operation would make a node its own ancestor.
+    WouldCreateCycle,
+    /// The referent `node` is not a child of the given parent.
+    ReferentNotChild,
+    /// Two `node`s that must differ are the same.
+    SameNode,
+    /// A stream of `IterMovement`s doesn't describe a pre-order or post-order walk of a forest.
+    InvalidMovement,
+}
+
+impl fmt::Display for ForestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match *self {
+            ForestError::InvalidNode => "invalid node",
+            ForestError::WouldCreateCycle => "operation would create a cycle",
+            ForestError::ReferentNotChild => "referent node is not a child of the parent node",
+            ForestError::SameNode => "nodes must be different",
+            ForestError::InvalidMovement => "movements don't describe a forest",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl Error for ForestError {}
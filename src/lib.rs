@@ -1,7 +1,15 @@
+mod axis;
 mod collections;
+mod cursor;
+mod error;
 mod navigator;
 mod primitive;
+mod replay;
+mod transfer;
 
+pub use axis::*;
 pub use collections::*;
+pub use cursor::*;
+pub use error::*;
 pub use navigator::*;
 pub use primitive::*;
@@ -14,6 +14,17 @@ pub enum NavigateDir {
     Right,
 }
 
+impl NavigateDir {
+    /// Returns the depth reached by navigating in this direction from `depth`.
+    pub(crate) fn apply_depth(self, depth: usize) -> usize {
+        match self {
+            NavigateDir::Up(_) => depth - 1,
+            NavigateDir::Down => depth + 1,
+            NavigateDir::Left | NavigateDir::Right => depth,
+        }
+    }
+}
+
 pub trait BiIterator: Iterator {
     fn prev(&mut self) -> Option<Self::Item>;
 }
@@ -2,35 +2,67 @@
 
 use super::navigator::*;
 use std::marker::PhantomData;
-use std::mem;
+use std::ops::{Index, IndexMut};
+use std::ptr;
 
-const CURSOR_INVALID_POS: usize = ::std::usize::MAX;
+const CURSOR_INVALID_POS: usize = usize::MAX;
+
+/// Index of the header entry, which carries forest-wide bookkeeping.
+pub(crate) const HEADER_IDX: usize = 0;
+
+/// Identity shared by all forests that didn't opt into a unique one.
+pub(crate) const ANONYMOUS_FOREST_ID: u32 = 0;
 
 /// A marker for a position in a `forest`.
 ///
 /// It is a `Copy` type, and very cheap to move around.
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
-pub struct node(usize);
+/// It also remembers the generation of the slot it points to, so a handle
+/// to a removed node is detected as stale even after its slot is reused,
+/// and the identity of its forest, if that forest opted into one.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct node {
+    idx: usize,
+    generation: u32,
+    forest_id: u32,
+}
 
 impl node {
     #[inline]
     pub(crate) fn new_invalid() -> Self {
-        node(CURSOR_INVALID_POS)
+        node {
+            idx: CURSOR_INVALID_POS,
+            generation: 0,
+            forest_id: ANONYMOUS_FOREST_ID,
+        }
     }
 
     #[inline]
-    pub(crate) unsafe fn new_with_idx(idx: usize) -> Self {
-        node(idx)
+    pub(crate) unsafe fn new_with_idx(idx: usize, generation: u32, forest_id: u32) -> Self {
+        node {
+            idx,
+            generation,
+            forest_id,
+        }
     }
 
     #[inline]
     pub(crate) fn is_invalid(self) -> bool {
-        self.0 == CURSOR_INVALID_POS
+        self.idx == CURSOR_INVALID_POS
+    }
+
+    #[inline]
+    pub(crate) fn generation(self) -> u32 {
+        self.generation
+    }
+
+    #[inline]
+    pub(crate) fn forest_id(self) -> u32 {
+        self.forest_id
     }
 
     #[inline]
     pub(crate) fn into_opt_node(self) -> Option<node> {
-        if self.0 == CURSOR_INVALID_POS {
+        if self.idx == CURSOR_INVALID_POS {
             None
         } else {
             Some(self)
@@ -39,17 +71,29 @@ impl node {
 
     #[inline]
     pub(crate) fn into_opt_idx(self) -> Option<usize> {
-        if self.0 == CURSOR_INVALID_POS {
+        if self.idx == CURSOR_INVALID_POS {
             None
         } else {
-            Some(self.0)
+            Some(self.idx)
         }
     }
 }
 
+#[derive(Clone)]
+pub(crate) enum EntryData<T> {
+    /// The header entry. Its `child_first` and `child_last` links are the first
+    /// and last top-level nodes, and its `next` link heads the list of vacant slots.
+    Header { len: usize, forest_id: u32 },
+    Occupied(T),
+    /// A removed entry, chained to the next vacant slot through `next`.
+    Vacant,
+}
+
 #[derive(Clone)]
 pub(crate) struct ForestEntry<T> {
-    pub(crate) data: T,
+    pub(crate) data: EntryData<T>,
+    /// Bumped every time the slot is vacated.
+    pub(crate) generation: u32,
     pub(crate) parent: node,
     pub(crate) prev: node,
     pub(crate) next: node,
@@ -59,8 +103,21 @@ pub(crate) struct ForestEntry<T> {
 
 impl<T> ForestEntry<T> {
     pub(crate) fn new(v: T) -> Self {
+        Self::with_data(EntryData::Occupied(v))
+    }
+
+    pub(crate) fn new_header(forest_id: u32) -> Self {
+        Self::with_data(EntryData::Header { len: 0, forest_id })
+    }
+
+    pub(crate) fn new_vacant() -> Self {
+        Self::with_data(EntryData::Vacant)
+    }
+
+    fn with_data(data: EntryData<T>) -> Self {
         ForestEntry {
-            data: v,
+            data,
+            generation: 0,
             parent: node::new_invalid(),
             prev: node::new_invalid(),
             next: node::new_invalid(),
@@ -68,53 +125,139 @@ impl<T> ForestEntry<T> {
             child_last: node::new_invalid(),
         }
     }
+
+    #[inline]
+    pub(crate) fn is_occupied(&self) -> bool {
+        matches!(self.data, EntryData::Occupied(_))
+    }
 }
 
 /// A dynamic sized type representing a forest with a contiguous region.
+#[repr(transparent)]
 pub struct forest<T> {
     data: [ForestEntry<T>],
 }
 
 impl<T> forest<T> {
+    /// Returns the number of nodes in the forest.
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.raw().len()
     }
 
-    pub fn iter(&self) -> Iter<T> {
-        Iter::new(self)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        IterMut::new(self)
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self, node::new_invalid())
     }
 
-    pub(crate) fn get_inner_ptr(&self, cursor: node) -> *const T {
-        if let Some(idx) = cursor.into_opt_idx() {
-            unsafe {
-                let entry: &ForestEntry<T> = self.data.get_unchecked(idx);
-                &entry.data
-            }
-        } else {
-            ::std::ptr::null()
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self, node::new_invalid())
+    }
+
+    /// Returns an iterator over `n` and its descendants.
+    ///
+    /// Both directions of iteration stop at the boundary of the subtree,
+    /// and navigation can't leave it either.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a live node of this forest.
+    pub fn iter_subtree(&self, n: node) -> Iter<'_, T> {
+        let _ = self.live_idx(n).expect("invalid node");
+        Iter::new(self, n)
+    }
+
+    /// Mutable version of `iter_subtree`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a live node of this forest.
+    pub fn iter_subtree_mut(&mut self, n: node) -> IterMut<'_, T> {
+        let _ = self.live_idx(n).expect("invalid node");
+        IterMut::new(self, n)
+    }
+
+    /// Returns a reference to the value of `n`, or `None` if `n` is not a live node of this forest.
+    pub fn get(&self, n: node) -> Option<&T> {
+        let idx = self.live_idx(n)?;
+        match self.data[idx].data {
+            EntryData::Occupied(ref v) => Some(v),
+            _ => None,
         }
     }
 
-    pub(crate) fn get_inner_ptr_mut(&mut self, cursor: node) -> *mut T {
-        if let Some(idx) = cursor.into_opt_idx() {
-            unsafe {
-                let entry: &mut ForestEntry<T> = self.data.get_unchecked_mut(idx);
-                &mut entry.data
+    /// Returns a mutable reference to the value of `n`, or `None` if `n` is not a live node of this forest.
+    pub fn get_mut(&mut self, n: node) -> Option<&mut T> {
+        let idx = self.live_idx(n)?;
+        match self.data[idx].data {
+            EntryData::Occupied(ref mut v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns mutable references to the values of several nodes at once.
+    ///
+    /// Returns `None` if any of the nodes is not a live node of this forest,
+    /// or if the same node is given more than once.
+    pub fn get_many_mut<const N: usize>(&mut self, nodes: [node; N]) -> Option<[&mut T; N]> {
+        let mut idxs = [HEADER_IDX; N];
+        for (i, &n) in nodes.iter().enumerate() {
+            let idx = self.live_idx(n)?;
+            if idxs[..i].contains(&idx) {
+                return None;
             }
+            idxs[i] = idx;
+        }
+        let raw = RawForest::from_mut(self);
+        // every index is distinct and occupied, so the borrows are disjoint.
+        Some(idxs.map(|idx| unsafe { &mut *raw.value_ptr_mut(raw.node_at(idx)) }))
+    }
+
+    #[inline]
+    pub(crate) fn raw(&self) -> RawForest<T> {
+        RawForest::from_ref(self)
+    }
+
+    #[inline]
+    pub(crate) fn forest_id(&self) -> u32 {
+        self.raw().forest_id()
+    }
+
+    /// Returns a handle to the entry at `idx`, carrying its current generation.
+    #[inline]
+    pub(crate) fn node_at(&self, idx: usize) -> node {
+        self.raw().node_at(idx)
+    }
+
+    /// Returns the index of the entry `n` points to, if it is a live node of this forest.
+    pub(crate) fn live_idx(&self, n: node) -> Option<usize> {
+        let idx = self.raw().live_idx(n)?;
+        if self.data[idx].is_occupied() {
+            Some(idx)
         } else {
-            ::std::ptr::null_mut()
+            None
         }
     }
+
+    pub(crate) fn seek_entry(&self, pos: SeekPos) -> node {
+        self.raw().seek_entry(node::new_invalid(), pos)
+    }
+}
+
+impl<T> Index<node> for forest<T> {
+    type Output = T;
+
+    fn index(&self, n: node) -> &T {
+        self.get(n).expect("invalid node")
+    }
 }
 
-#[repr(C)]
-struct ForestRefRepr<T> {
-    pub data: *const T,
-    pub len: usize,
+impl<T> IndexMut<node> for forest<T> {
+    fn index_mut(&mut self, n: node) -> &mut T {
+        self.get_mut(n).expect("invalid node")
+    }
 }
 
 #[inline]
@@ -122,7 +265,7 @@ pub(crate) unsafe fn forest_ref_from_raw_parts<'a, T>(
     p: *const ForestEntry<T>,
     len: usize,
 ) -> &'a forest<T> {
-    mem::transmute(ForestRefRepr { data: p, len: len })
+    &*(ptr::slice_from_raw_parts(p, len) as *const forest<T>)
 }
 
 #[inline]
@@ -130,7 +273,136 @@ pub(crate) unsafe fn forest_ref_from_raw_parts_mut<'a, T>(
     p: *mut ForestEntry<T>,
     len: usize,
 ) -> &'a mut forest<T> {
-    mem::transmute(ForestRefRepr { data: p, len: len })
+    &mut *(ptr::slice_from_raw_parts_mut(p, len) as *mut forest<T>)
+}
+
+/// A raw view of the entries of a `forest`.
+///
+/// Traversal reads the entries through it one field at a time, so it never creates
+/// a reference covering a value that `IterMut` or `get_many_mut` has handed out.
+pub(crate) struct RawForest<T> {
+    entries: *mut ForestEntry<T>,
+    len: usize,
+}
+
+impl<T> Clone for RawForest<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RawForest<T> {}
+
+macro_rules! raw_forest_link {
+    ($name:ident) => {
+        #[inline]
+        pub(crate) fn $name(self, idx: usize) -> node {
+            unsafe { ptr::addr_of!((*self.entry(idx)).$name).read() }
+        }
+    };
+}
+
+impl<T> RawForest<T> {
+    #[inline]
+    pub(crate) fn from_ref(f: &forest<T>) -> Self {
+        RawForest {
+            entries: f.data.as_ptr() as *mut ForestEntry<T>,
+            len: f.data.len(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn from_mut(f: &mut forest<T>) -> Self {
+        RawForest {
+            entries: f.data.as_mut_ptr(),
+            len: f.data.len(),
+        }
+    }
+
+    #[inline]
+    fn entry(self, idx: usize) -> *mut ForestEntry<T> {
+        assert!(idx < self.len, "node out of bound");
+        unsafe { self.entries.add(idx) }
+    }
+
+    raw_forest_link!(parent);
+    raw_forest_link!(prev);
+    raw_forest_link!(next);
+    raw_forest_link!(child_first);
+    raw_forest_link!(child_last);
+
+    #[inline]
+    pub(crate) fn generation(self, idx: usize) -> u32 {
+        unsafe { ptr::addr_of!((*self.entry(idx)).generation).read() }
+    }
+
+    pub(crate) fn len(self) -> usize {
+        unsafe {
+            match *ptr::addr_of!((*self.entry(HEADER_IDX)).data) {
+                EntryData::Header { len, .. } => len,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    pub(crate) fn forest_id(self) -> u32 {
+        unsafe {
+            match *ptr::addr_of!((*self.entry(HEADER_IDX)).data) {
+                EntryData::Header { forest_id, .. } => forest_id,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn node_at(self, idx: usize) -> node {
+        unsafe { node::new_with_idx(idx, self.generation(idx), self.forest_id()) }
+    }
+
+    /// Returns the index of the entry `n` points to, if its generation is current.
+    ///
+    /// Vacant slots always have a newer generation than any handle to them,
+    /// so this doesn't need to look at the value.
+    pub(crate) fn live_idx(self, n: node) -> Option<usize> {
+        if n.forest_id() != self.forest_id() {
+            return None;
+        }
+        let idx = n.into_opt_idx()?;
+        if idx == HEADER_IDX || idx >= self.len || self.generation(idx) != n.generation() {
+            return None;
+        }
+        Some(idx)
+    }
+
+    /// Returns a pointer to the value of `cursor`, or null if there is none.
+    pub(crate) fn value_ptr(self, cursor: node) -> *const T {
+        if let Some(idx) = cursor.into_opt_idx() {
+            unsafe {
+                match *ptr::addr_of!((*self.entry(idx)).data) {
+                    EntryData::Occupied(ref v) => v,
+                    _ => ptr::null(),
+                }
+            }
+        } else {
+            ptr::null()
+        }
+    }
+
+    /// Returns a mutable pointer to the value of `cursor`, or null if there is none.
+    ///
+    /// The view must have been created by `from_mut`.
+    pub(crate) fn value_ptr_mut(self, cursor: node) -> *mut T {
+        if let Some(idx) = cursor.into_opt_idx() {
+            unsafe {
+                match *ptr::addr_of_mut!((*self.entry(idx)).data) {
+                    EntryData::Occupied(ref mut v) => v,
+                    _ => ptr::null_mut(),
+                }
+            }
+        } else {
+            ptr::null_mut()
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -157,6 +429,19 @@ pub enum IterMovement {
 }
 
 impl IterMovement {
+    /// Returns the depth reached by making this movement from `depth`.
+    fn apply_depth(self, depth: usize) -> usize {
+        match self {
+            IterMovement::None | IterMovement::Left | IterMovement::Right => depth,
+            IterMovement::GotoStart | IterMovement::GotoEnd => 0,
+            IterMovement::DownFirst(k)
+            | IterMovement::DownLast(k)
+            | IterMovement::RightDownFirstN(k)
+            | IterMovement::LeftDownLastN(k) => depth + k,
+            IterMovement::Up(k) | IterMovement::UpNRight(k) | IterMovement::UpNLeft(k) => depth - k,
+        }
+    }
+
     fn join(self, other: Self) -> Self {
         match (self, other) {
             (IterMovement::None, b) => b,
@@ -192,8 +477,7 @@ impl IterMovement {
 /// You can call `value` on this iterator to filter out the movement information.
 pub struct Iter<'a, T: 'a> {
     data: &'a forest<T>,
-    mode: IterMode,
-    cursor: (node, bool),
+    state: IterState,
 }
 
 /// Mutable forest bidirectional iterator and navigator.
@@ -202,22 +486,34 @@ pub struct Iter<'a, T: 'a> {
 /// The movement information can be used to reconstruct this forest.
 /// You can call `value` on this iterator to filter out the movement information.
 pub struct IterMut<'a, T: 'a> {
-    data: &'a mut forest<T>,
-    mode: IterMode,
-    cursor: (node, bool),
+    data: RawForest<T>,
+    state: IterState,
+    phantom: PhantomData<&'a mut forest<T>>,
 }
 
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
 impl<'a, T: 'a> Iter<'a, T> {
-    fn new(f: &'a forest<T>) -> Self {
+    fn new(f: &'a forest<T>, root: node) -> Self {
         Iter {
             data: f,
-            mode: IterMode::PreOrder,
-            cursor: (node::new_invalid(), true),
+            state: IterState::new(f.raw(), root),
         }
     }
 
     pub fn mode(mut self, mode: IterMode) -> Self {
-        self.mode = mode;
+        self.state.mode = mode;
+        self.state.reset_remaining(self.data.raw());
+        self
+    }
+
+    /// Never descends more than `max_depth` levels below the top-level nodes,
+    /// or below the subtree root for subtree iterators.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.state.max_depth = Some(max_depth);
+        self.state.reset_remaining(self.data.raw());
         self
     }
 
@@ -228,26 +524,117 @@ impl<'a, T: 'a> Iter<'a, T> {
         }
     }
 
-    pub fn last_visited_node(&self) -> Option<node> {
-        if self.cursor.0.is_invalid() {
-            None
-        } else {
-            Some(self.cursor.0)
+    /// Turns this into an `ExactSizeIterator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the iterator walks the whole forest, without a depth limit,
+    /// and hasn't been moved other than by `next`, `prev` and `next_back`.
+    pub fn exact(self) -> ExactIter<Self> {
+        assert!(self.state.remaining.is_some(), "iterator length unknown");
+        ExactIter { iter: self }
+    }
+
+    /// Switches to `IterMode::Both` and reports each visit as an entering or leaving event.
+    pub fn events(self) -> Events<&'a T, Self> {
+        Events {
+            iter: self.mode(IterMode::Both),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Moves past the descendants of the node just entered, so they won't be visited.
+    ///
+    /// In `IterMode::Both`, the next item is the leaving visit of that node.
+    /// Does nothing if the last visit wasn't an entering one.
+    /// If the skipped part reaches what was already yielded from the back, the
+    /// iteration ends.
+    pub fn skip_subtree(&mut self) {
+        self.state.skip_subtree();
+    }
+
+    /// Visits nodes as usual, but skips the descendants of nodes whose value matches `pred`.
+    pub fn prune_where<P: FnMut(&T) -> bool>(self, pred: P) -> PruneWhere<Self, P> {
+        PruneWhere { iter: self, pred }
+    }
+
+    /// Places the cursor on `n`, entering or leaving it, and returns its value.
+    ///
+    /// Iteration continues forward or backward from there. Returns `None` and leaves
+    /// the cursor alone if `n` is not a live node within reach of this iterator, or
+    /// if its visit was already yielded from the back.
+    pub fn seek_node(&mut self, n: node, entry: bool) -> Option<&'a T> {
+        let n = self.state.seek_node(self.data.raw(), n, entry)?;
+        unsafe { self.data.raw().value_ptr(n).as_ref() }
+    }
+
+    /// Returns a checkpoint of the cursor, to be given back to `restore`.
+    pub fn save(&self) -> IterCheckpoint {
+        self.state.save()
+    }
+
+    /// Moves the cursor back to a checkpoint taken by `save`.
+    ///
+    /// If the visits after the checkpoint were already yielded from the back, the
+    /// iteration ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint doesn't belong to an iterator over the same nodes.
+    pub fn restore(&mut self, checkpoint: IterCheckpoint) {
+        self.state.restore(self.data.raw(), checkpoint);
+    }
+
+    /// Reports the visited node along with the movement and the borrowed value.
+    pub fn with_nodes(self) -> WithNodes<&'a T, Self> {
+        WithNodes {
+            iter: self,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns only the visited nodes.
+    pub fn nodes(self) -> Nodes<&'a T, Self> {
+        Nodes {
+            iter: self,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Reports the depth and the node of each visit along with the borrowed value.
+    pub fn with_depth(self) -> WithDepth<&'a T, Self> {
+        WithDepth {
+            iter: self,
+            phantom: PhantomData,
         }
     }
+
+    pub fn last_visited_node(&self) -> Option<node> {
+        self.state.cursor.0.into_opt_node()
+    }
 }
 
 impl<'a, T> IterMut<'a, T> {
-    fn new(f: &'a mut forest<T>) -> Self {
+    fn new(f: &'a mut forest<T>, root: node) -> Self {
+        let data = RawForest::from_mut(f);
         IterMut {
-            data: f,
-            mode: IterMode::PreOrder,
-            cursor: (node::new_invalid(), true),
+            data,
+            state: IterState::new(data, root),
+            phantom: PhantomData,
         }
     }
 
     pub fn mode(mut self, mode: IterMode) -> Self {
-        self.mode = mode;
+        self.state.mode = mode;
+        self.state.reset_remaining(self.data);
+        self
+    }
+
+    /// Never descends more than `max_depth` levels below the top-level nodes,
+    /// or below the subtree root for subtree iterators.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.state.max_depth = Some(max_depth);
+        self.state.reset_remaining(self.data);
         self
     }
 
@@ -258,136 +645,503 @@ impl<'a, T> IterMut<'a, T> {
         }
     }
 
-    pub fn last_visited_node(&self) -> Option<node> {
-        if self.cursor.0.is_invalid() {
-            None
-        } else {
-            Some(self.cursor.0)
+    /// Turns this into an `ExactSizeIterator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the iterator walks the whole forest, without a depth limit,
+    /// and hasn't been moved other than by `next`, `prev` and `next_back`.
+    pub fn exact(self) -> ExactIter<Self> {
+        assert!(self.state.remaining.is_some(), "iterator length unknown");
+        ExactIter { iter: self }
+    }
+
+    /// Switches to `IterMode::Both` and reports each visit as an entering or leaving event.
+    ///
+    /// Only entering events lend the value, so that it is never borrowed twice.
+    pub fn events(self) -> EventsMut<'a, T> {
+        EventsMut {
+            iter: self.mode(IterMode::Both),
         }
     }
-}
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub(crate) enum IterDir {
-    Next,
-    Prev,
-}
+    /// Moves past the descendants of the node just entered, so they won't be visited.
+    ///
+    /// In `IterMode::Both`, the next item is the leaving visit of that node.
+    /// Does nothing if the last visit wasn't an entering one.
+    /// If the skipped part reaches what was already yielded from the back, the
+    /// iteration ends.
+    pub fn skip_subtree(&mut self) {
+        self.state.skip_subtree();
+    }
 
-impl<T> forest<T> {
-    pub(crate) fn top_first_entry(&self) -> node {
-        if self.len() == 0 {
-            node::new_invalid()
-        } else {
-            let mut cur_idx = 0;
-
-            loop {
-                let cur_entry = &self.data[cur_idx];
-                let parent_cursor = cur_entry.parent;
-                if let Some(parent_idx) = parent_cursor.into_opt_idx() {
-                    cur_idx = parent_idx;
-                } else {
-                    break;
-                }
-            }
+    /// Visits nodes as usual, but skips the descendants of nodes whose value matches `pred`.
+    pub fn prune_where<P: FnMut(&T) -> bool>(self, pred: P) -> PruneWhere<Self, P> {
+        PruneWhere { iter: self, pred }
+    }
 
-            loop {
-                let cur_entry = &self.data[cur_idx];
-                let prev_cursor = cur_entry.prev;
-                if let Some(prev_idx) = prev_cursor.into_opt_idx() {
-                    cur_idx = prev_idx;
-                } else {
-                    break;
-                }
-            }
+    /// Places the cursor on `n`, entering or leaving it, and returns its value.
+    ///
+    /// Iteration continues forward or backward from there. Returns `None` and leaves
+    /// the cursor alone if `n` is not a live node within reach of this iterator, or
+    /// if its visit was already yielded from the back.
+    ///
+    /// # Safety
+    ///
+    /// Iteration from `n` may yield values that were yielded before. The caller must
+    /// make sure those earlier references are no longer used by then.
+    pub unsafe fn seek_node(&mut self, n: node, entry: bool) -> Option<&mut T> {
+        let n = self.state.seek_node(self.data, n, entry)?;
+        unsafe { self.data.value_ptr_mut(n).as_mut() }
+    }
 
-            unsafe { node::new_with_idx(cur_idx) }
-        }
+    /// Returns a checkpoint of the cursor, to be given back to `restore`.
+    pub fn save(&self) -> IterCheckpoint {
+        self.state.save()
     }
 
-    pub(crate) fn seek_entry(&self, pos: SeekPos) -> node {
-        let mut cur = self.top_first_entry();
-        if cur.is_invalid() {
-            return cur;
+    /// Moves the cursor back to a checkpoint taken by `save`.
+    ///
+    /// If the visits after the checkpoint were already yielded from the back, the
+    /// iteration ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint doesn't belong to an iterator over the same nodes.
+    ///
+    /// # Safety
+    ///
+    /// Iteration from the checkpoint yields the values after it once more. The caller
+    /// must make sure the references yielded before `restore` are no longer used by then.
+    pub unsafe fn restore(&mut self, checkpoint: IterCheckpoint) {
+        self.state.restore(self.data, checkpoint);
+    }
+
+    /// Reports the visited node along with the movement and the borrowed value.
+    pub fn with_nodes(self) -> WithNodes<&'a mut T, Self> {
+        WithNodes {
+            iter: self,
+            phantom: PhantomData,
         }
+    }
 
-        match pos {
-            SeekPos::TopLast | SeekPos::BottomLast => loop {
-                let cur_entry = &self.data[cur.into_opt_idx().unwrap()];
-                let next_cursor = cur_entry.next;
-                if next_cursor.is_invalid() {
-                    break;
-                }
-                cur = next_cursor;
-            },
-            SeekPos::TopFirst | SeekPos::BottomFirst => {}
+    /// Returns only the visited nodes.
+    pub fn nodes(self) -> Nodes<&'a mut T, Self> {
+        Nodes {
+            iter: self,
+            phantom: PhantomData,
         }
+    }
 
-        match pos {
-            SeekPos::TopFirst | SeekPos::TopLast => {}
-            SeekPos::BottomFirst => loop {
-                let cur_entry = &self.data[cur.into_opt_idx().unwrap()];
-                let child_cursor = cur_entry.child_first;
-                if child_cursor.is_invalid() {
-                    break;
-                }
-                cur = child_cursor;
-            },
-            SeekPos::BottomLast => loop {
-                let cur_entry = &self.data[cur.into_opt_idx().unwrap()];
-                let child_cursor = cur_entry.child_last;
-                if child_cursor.is_invalid() {
-                    break;
-                }
-                cur = child_cursor;
-            },
+    /// Reports the depth and the node of each visit along with the borrowed value.
+    pub fn with_depth(self) -> WithDepth<&'a mut T, Self> {
+        WithDepth {
+            iter: self,
+            phantom: PhantomData,
         }
-        cur
     }
 
-    pub(crate) fn iterate_once(
-        &self,
-        (cursor, entry): (node, bool),
-        dir: IterDir,
-    ) -> (IterMovement, (node, bool)) {
-        if let Some(idx) = cursor.into_opt_idx() {
-            let cur_entry = &self.data[idx];
-            match (entry, dir) {
-                (true, IterDir::Next) => {
-                    let new_cursor = cur_entry.child_first;
-                    if new_cursor.is_invalid() {
-                        (IterMovement::None, (cursor, false))
-                    } else {
-                        (IterMovement::DownFirst(1), (new_cursor, true))
-                    }
-                }
-                (false, IterDir::Next) => {
-                    let mut new_cursor = cur_entry.next;
-                    if new_cursor.is_invalid() {
-                        new_cursor = cur_entry.parent;
-                        if new_cursor.is_invalid() {
-                            (IterMovement::GotoEnd, (new_cursor, false))
-                        } else {
-                            (IterMovement::Up(1), (new_cursor, false))
-                        }
-                    } else {
-                        (IterMovement::Right, (new_cursor, true))
-                    }
-                }
-                (true, IterDir::Prev) => {
-                    let mut new_cursor = cur_entry.child_last;
-                    if new_cursor.is_invalid() {
-                        new_cursor = cur_entry.prev;
-                        if new_cursor.is_invalid() {
+    pub fn last_visited_node(&self) -> Option<node> {
+        self.state.cursor.0.into_opt_node()
+    }
+}
+
+impl<'a, T> LastVisit for Iter<'a, T> {
+    fn last_visit(&self) -> Option<IterEvent<node>> {
+        self.state.last_visit()
+    }
+
+    fn last_depth(&self) -> usize {
+        self.state.depth
+    }
+
+    fn last_visit_back(&self) -> Option<IterEvent<node>> {
+        self.state.last_visit_back()
+    }
+
+    fn last_depth_back(&self) -> usize {
+        self.state.back_depth
+    }
+}
+
+impl<'a, T> LastVisit for IterMut<'a, T> {
+    fn last_visit(&self) -> Option<IterEvent<node>> {
+        self.state.last_visit()
+    }
+
+    fn last_depth(&self) -> usize {
+        self.state.depth
+    }
+
+    fn last_visit_back(&self) -> Option<IterEvent<node>> {
+        self.state.last_visit_back()
+    }
+
+    fn last_depth_back(&self) -> usize {
+        self.state.back_depth
+    }
+}
+
+/// A saved iterator cursor, see `Iter::save`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct IterCheckpoint {
+    cursor: (node, bool),
+    depth: usize,
+}
+
+/// The cursors shared by `Iter` and `IterMut`.
+///
+/// `cursor` is the last visit returned from the front, by `next` or `prev`,
+/// and `back` the last visit returned by `next_back`. An invalid `back` means
+/// `next_back` hasn't been called yet.
+#[derive(Copy, Clone)]
+struct IterState {
+    mode: IterMode,
+    root: node,
+    max_depth: Option<usize>,
+    cursor: (node, bool),
+    depth: usize,
+    skipping: bool,
+    back: (node, bool),
+    back_depth: usize,
+    /// The number of visits left between the cursors, if known without walking.
+    remaining: Option<usize>,
+}
+
+impl IterState {
+    fn new<T>(data: RawForest<T>, root: node) -> Self {
+        let mut state = IterState {
+            mode: IterMode::PreOrder,
+            root,
+            max_depth: None,
+            cursor: (node::new_invalid(), true),
+            depth: 0,
+            skipping: false,
+            back: (node::new_invalid(), false),
+            back_depth: 0,
+            remaining: None,
+        };
+        state.reset_remaining(data);
+        state
+    }
+
+    /// Counts the visits up front when walking the whole forest from the start,
+    /// which is the only case where the forest length tells it.
+    fn reset_remaining<T>(&mut self, data: RawForest<T>) {
+        let fresh = self.cursor == (node::new_invalid(), true) && self.back.0.is_invalid();
+        self.remaining = if fresh && self.root.is_invalid() && self.max_depth.is_none() {
+            match self.mode {
+                IterMode::PreOrder | IterMode::PostOrder => Some(data.len()),
+                IterMode::Both => Some(data.len() * 2),
+            }
+        } else {
+            None
+        };
+    }
+
+    fn scope(&self) -> (node, Option<usize>) {
+        (self.root, self.max_depth)
+    }
+
+    fn next<T>(&mut self, data: RawForest<T>) -> Option<(IterMovement, node)> {
+        let (movement, new_pair, new_depth) = if self.skipping {
+            // the node just entered is treated as a leaf.
+            let leave = (self.cursor.0, false);
+            match self.mode {
+                IterMode::Both => (IterMovement::None, leave, self.depth),
+                IterMode::PreOrder | IterMode::PostOrder => {
+                    data.iterate_entry(self.scope(), leave, self.depth, IterDir::Next, self.mode)
+                }
+            }
+        } else {
+            data.iterate_entry(self.scope(), self.cursor, self.depth, IterDir::Next, self.mode)
+        };
+        if self.meets_back(data, new_pair, self.skipping) {
+            if self.skipping {
+                // the skipped subtree reaches into what the back returned.
+                self.finish();
+            }
+            self.remaining = Some(0);
+            return None;
+        }
+        self.cursor = new_pair;
+        self.depth = new_depth;
+        self.skipping = false;
+        let n = new_pair.0.into_opt_node();
+        self.remaining = match n {
+            Some(_) => self.remaining.map(|remaining| remaining - 1),
+            None => Some(0),
+        };
+        n.map(|n| (movement, n))
+    }
+
+    fn prev<T>(&mut self, data: RawForest<T>) -> Option<(IterMovement, node)> {
+        self.skipping = false;
+        let (movement, new_pair, new_depth) =
+            data.iterate_entry(self.scope(), self.cursor, self.depth, IterDir::Prev, self.mode);
+        if !self.cursor.0.is_invalid() {
+            // the node the cursor was on is ahead again.
+            self.remaining = self.remaining.map(|remaining| remaining + 1);
+        }
+        self.cursor = new_pair;
+        self.depth = new_depth;
+        new_pair.0.into_opt_node().map(|n| (movement, n))
+    }
+
+    fn next_back<T>(&mut self, data: RawForest<T>) -> Option<(IterMovement, node)> {
+        if self.cursor == (node::new_invalid(), false) {
+            // the front already went through everything.
+            return None;
+        }
+        let (movement, new_pair, new_depth) =
+            data.iterate_entry(self.scope(), self.back, self.back_depth, IterDir::Prev, self.mode);
+        if new_pair.0.is_invalid() || new_pair == self.cursor {
+            self.remaining = Some(0);
+            return None;
+        }
+        self.back = new_pair;
+        self.back_depth = new_depth;
+        self.remaining = self.remaining.map(|remaining| remaining - 1);
+        Some((movement, new_pair.0))
+    }
+
+    fn navigate<T>(&mut self, data: RawForest<T>, dir: NavigateDir) -> Option<node> {
+        self.skipping = false;
+        if dir == NavigateDir::Down && Some(self.depth) == self.max_depth {
+            return None;
+        }
+        let new_pair = data.navigate_entry(self.root, self.cursor.0, dir)?;
+        if self.meets_back(data, new_pair, true) {
+            return None;
+        }
+        self.cursor = new_pair;
+        self.depth = dir.apply_depth(self.depth);
+        self.remaining = None;
+        Some(new_pair.0)
+    }
+
+    fn seek<T>(&mut self, data: RawForest<T>, pos: SeekPos) -> Option<node> {
+        self.skipping = false;
+        let (new_pos, new_depth) = data.seek_entry_limited(self.scope(), pos);
+        if new_pos.is_invalid() {
+            return None;
+        };
+        let new_entry = match self.mode {
+            IterMode::PreOrder => true,
+            IterMode::PostOrder => false,
+            IterMode::Both => match pos {
+                SeekPos::TopFirst | SeekPos::BottomFirst => true,
+                SeekPos::TopLast | SeekPos::BottomLast => false,
+            },
+        };
+        if self.meets_back(data, (new_pos, new_entry), true) {
+            return None;
+        }
+        self.cursor = (new_pos, new_entry);
+        self.depth = new_depth;
+        self.remaining = None;
+        Some(new_pos)
+    }
+
+    fn seek_node<T>(&mut self, data: RawForest<T>, n: node, entry: bool) -> Option<node> {
+        let depth = data.scope_depth(self.scope(), n)?;
+        if self.meets_back(data, (n, entry), true) {
+            return None;
+        }
+        self.cursor = (n, entry);
+        self.depth = depth;
+        self.skipping = false;
+        self.remaining = None;
+        Some(n)
+    }
+
+    fn skip_subtree(&mut self) {
+        if self.cursor.1 && !self.cursor.0.is_invalid() {
+            self.skipping = true;
+            self.remaining = None;
+        }
+    }
+
+    fn save(&self) -> IterCheckpoint {
+        IterCheckpoint {
+            cursor: self.cursor,
+            depth: self.depth,
+        }
+    }
+
+    fn restore<T>(&mut self, data: RawForest<T>, checkpoint: IterCheckpoint) {
+        let (n, _) = checkpoint.cursor;
+        if !n.is_invalid() {
+            let depth = data.scope_depth(self.scope(), n);
+            assert!(depth == Some(checkpoint.depth), "invalid checkpoint");
+        }
+        self.skipping = false;
+        if self.meets_back(data, checkpoint.cursor, true) {
+            // nothing is left between the checkpoint and the back.
+            self.finish();
+            return;
+        }
+        self.cursor = checkpoint.cursor;
+        self.depth = checkpoint.depth;
+        self.remaining = None;
+    }
+
+    /// Ends the iteration from both sides.
+    fn finish(&mut self) {
+        self.cursor = (node::new_invalid(), false);
+        self.depth = 0;
+        self.skipping = false;
+        self.remaining = Some(0);
+    }
+
+    /// Returns whether moving the front to `pair` would run into the visits already
+    /// returned from the back.
+    ///
+    /// Stepping can only land right on the back cursor, but a `jump` can carry the
+    /// front past it.
+    fn meets_back<T>(&self, data: RawForest<T>, pair: (node, bool), jump: bool) -> bool {
+        if self.back.0.is_invalid() {
+            false
+        } else if jump {
+            !pair.0.is_invalid() && !data.visit_precedes(pair, self.back)
+        } else {
+            pair == self.back
+        }
+    }
+
+    fn last_visit(&self) -> Option<IterEvent<node>> {
+        let (cursor, entry) = self.cursor;
+        cursor.into_opt_node().map(|n| IterEvent::new(n, entry))
+    }
+
+    fn last_visit_back(&self) -> Option<IterEvent<node>> {
+        let (back, entry) = self.back;
+        back.into_opt_node().map(|n| IterEvent::new(n, entry))
+    }
+
+    /// Returns the number of visits left if it is known, or else a bound from the forest length.
+    fn size_hint<T>(&self, data: RawForest<T>) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => match self.mode {
+                IterMode::PreOrder | IterMode::PostOrder => (0, Some(data.len())),
+                IterMode::Both => (0, Some(data.len() * 2)),
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum IterDir {
+    Next,
+    Prev,
+}
+
+/// Traversal over a raw view.
+///
+/// Each of these takes a `root` that scopes the walk to the subtree of that node;
+/// an invalid `root` walks the whole forest.
+impl<T> RawForest<T> {
+    pub(crate) fn top_first_entry(self) -> node {
+        self.child_first(HEADER_IDX)
+    }
+
+    pub(crate) fn top_last_entry(self) -> node {
+        self.child_last(HEADER_IDX)
+    }
+
+    pub(crate) fn seek_entry(self, root: node, pos: SeekPos) -> node {
+        let mut cur = if root.is_invalid() {
+            match pos {
+                SeekPos::TopFirst | SeekPos::BottomFirst => self.top_first_entry(),
+                SeekPos::TopLast | SeekPos::BottomLast => self.top_last_entry(),
+            }
+        } else {
+            root
+        };
+        if cur.is_invalid() {
+            return cur;
+        }
+
+        match pos {
+            SeekPos::TopFirst | SeekPos::TopLast => {}
+            SeekPos::BottomFirst => loop {
+                let child_cursor = self.child_first(cur.into_opt_idx().unwrap());
+                if child_cursor.is_invalid() {
+                    break;
+                }
+                cur = child_cursor;
+            },
+            SeekPos::BottomLast => loop {
+                let child_cursor = self.child_last(cur.into_opt_idx().unwrap());
+                if child_cursor.is_invalid() {
+                    break;
+                }
+                cur = child_cursor;
+            },
+        }
+        cur
+    }
+
+    pub(crate) fn iterate_once(
+        self,
+        root: node,
+        (cursor, entry): (node, bool),
+        dir: IterDir,
+        descend: bool,
+    ) -> (IterMovement, (node, bool)) {
+        if let Some(idx) = cursor.into_opt_idx() {
+            match (entry, dir) {
+                (true, IterDir::Next) => {
+                    let new_cursor = if descend {
+                        self.child_first(idx)
+                    } else {
+                        node::new_invalid()
+                    };
+                    if new_cursor.is_invalid() {
+                        (IterMovement::None, (cursor, false))
+                    } else {
+                        (IterMovement::DownFirst(1), (new_cursor, true))
+                    }
+                }
+                (false, IterDir::Next) => {
+                    if cursor == root {
+                        return (IterMovement::GotoEnd, (node::new_invalid(), false));
+                    }
+                    let mut new_cursor = self.next(idx);
+                    if new_cursor.is_invalid() {
+                        new_cursor = self.parent(idx);
+                        if new_cursor.is_invalid() {
+                            (IterMovement::GotoEnd, (new_cursor, false))
+                        } else {
+                            (IterMovement::Up(1), (new_cursor, false))
+                        }
+                    } else {
+                        (IterMovement::Right, (new_cursor, true))
+                    }
+                }
+                (true, IterDir::Prev) => {
+                    if cursor == root {
+                        return (IterMovement::GotoStart, (node::new_invalid(), true));
+                    }
+                    let mut new_cursor = self.prev(idx);
+                    if new_cursor.is_invalid() {
+                        new_cursor = self.parent(idx);
+                        if new_cursor.is_invalid() {
                             (IterMovement::GotoStart, (new_cursor, true))
                         } else {
-                            (IterMovement::Left, (new_cursor, false))
+                            (IterMovement::Up(1), (new_cursor, true))
                         }
                     } else {
-                        (IterMovement::DownLast(1), (new_cursor, false))
+                        (IterMovement::Left, (new_cursor, false))
                     }
                 }
                 (false, IterDir::Prev) => {
-                    let new_cursor = cur_entry.child_last;
+                    let new_cursor = if descend {
+                        self.child_last(idx)
+                    } else {
+                        node::new_invalid()
+                    };
                     if new_cursor.is_invalid() {
                         (IterMovement::None, (cursor, true))
                     } else {
@@ -398,7 +1152,7 @@ impl<T> forest<T> {
         } else {
             match (entry, dir) {
                 (true, IterDir::Next) => {
-                    let new_cursor = self.seek_entry(SeekPos::TopFirst);
+                    let new_cursor = self.seek_entry(root, SeekPos::TopFirst);
                     if new_cursor.is_invalid() {
                         (IterMovement::None, (cursor, entry))
                     } else {
@@ -406,7 +1160,7 @@ impl<T> forest<T> {
                     }
                 }
                 (false, IterDir::Prev) => {
-                    let new_cursor = self.seek_entry(SeekPos::TopLast);
+                    let new_cursor = self.seek_entry(root, SeekPos::TopLast);
                     if new_cursor.is_invalid() {
                         (IterMovement::None, (cursor, entry))
                     } else {
@@ -418,43 +1172,163 @@ impl<T> forest<T> {
         }
     }
 
+    /// Moves to the next visit in `mode`, keeping track of the `depth` of the cursor
+    /// and never going below `max_depth`.
     pub(crate) fn iterate_entry(
-        &self,
+        self,
+        (root, max_depth): (node, Option<usize>),
         (mut cur_cursor, mut cur_entry): (node, bool),
+        mut depth: usize,
         dir: IterDir,
         mode: IterMode,
-    ) -> (IterMovement, (node, bool)) {
+    ) -> (IterMovement, (node, bool), usize) {
         let mut movement = IterMovement::None;
         loop {
+            let descend = !matches!(max_depth, Some(max_depth) if depth >= max_depth);
             let (new_movement, (new_cursor, new_entry)) =
-                self.iterate_once((cur_cursor, cur_entry), dir);
+                self.iterate_once(root, (cur_cursor, cur_entry), dir, descend);
+            depth = new_movement.apply_depth(depth);
             movement = movement.join(new_movement);
             cur_cursor = new_cursor;
             cur_entry = new_entry;
+            if cur_cursor.is_invalid() {
+                // either finished, or there is nothing to walk.
+                return (movement, (cur_cursor, cur_entry), depth);
+            }
             match movement {
                 | IterMovement::GotoStart | IterMovement::GotoEnd => {
-                    return (movement, (cur_cursor, cur_entry));
+                    return (movement, (cur_cursor, cur_entry), depth);
                 }
                 _ => match (cur_entry, mode) {
                     | (true, IterMode::PostOrder) | (false, IterMode::PreOrder) => continue,
                     _ => {
-                        return (movement, (cur_cursor, cur_entry));
+                        return (movement, (cur_cursor, cur_entry), depth);
                     }
                 },
             }
         }
     }
 
-    pub(crate) fn navigate_entry(&self, cursor: node, dir: NavigateDir) -> Option<(node, bool)> {
-        let idx = cursor.into_opt_idx()?;
-        let cur_entry = &self.data[idx];
-        let (new_cursor_idx, new_entry) = match dir {
-            NavigateDir::Up(up_entry) => (cur_entry.parent.into_opt_idx()?, up_entry),
-            NavigateDir::Down => (cur_entry.child_first.into_opt_idx()?, true),
-            NavigateDir::Left => (cur_entry.prev.into_opt_idx()?, true),
-            NavigateDir::Right => (cur_entry.next.into_opt_idx()?, true),
+    /// Returns the number of levels between `cursor` and the top of the walk.
+    pub(crate) fn depth_of(self, root: node, cursor: node) -> usize {
+        let mut depth = 0;
+        let mut cur = cursor;
+        while let Some(idx) = cur.into_opt_idx() {
+            if cur == root {
+                break;
+            }
+            cur = self.parent(idx);
+            if cur.is_invalid() {
+                break;
+            }
+            depth += 1;
+        }
+        depth
+    }
+
+    /// Returns the depth of `n` if it's a live node within `root` and `max_depth`.
+    pub(crate) fn scope_depth(
+        self,
+        (root, max_depth): (node, Option<usize>),
+        n: node,
+    ) -> Option<usize> {
+        self.live_idx(n)?;
+        if !root.is_invalid() && !self.ancestors_or_self_contain(n, root) {
+            return None;
+        }
+        let depth = self.depth_of(root, n);
+        match max_depth {
+            Some(max_depth) if depth > max_depth => None,
+            _ => Some(depth),
+        }
+    }
+
+    /// Returns whether visit `a` comes before visit `b`, in a walk over both visits of every node.
+    pub(crate) fn visit_precedes(self, (a, a_entry): (node, bool), (b, b_entry): (node, bool)) -> bool {
+        if a == b {
+            return a_entry && !b_entry;
+        }
+        let a_path = self.path_from_top(a);
+        let b_path = self.path_from_top(b);
+        let common = a_path.iter().zip(&b_path).take_while(|&(x, y)| x == y).count();
+        match (a_path.get(common), b_path.get(common)) {
+            // `a` is an ancestor of `b`.
+            (None, _) => a_entry,
+            // `b` is an ancestor of `a`.
+            (_, None) => !b_entry,
+            (Some(&a_branch), Some(&b_branch)) => self.sibling_precedes(a_branch, b_branch),
+        }
+    }
+
+    /// Returns the top-level ancestor-or-self of `n` down to `n`.
+    fn path_from_top(self, n: node) -> Vec<node> {
+        let mut path = vec![n];
+        let mut cur = n;
+        while let Some(idx) = cur.into_opt_idx() {
+            cur = self.parent(idx);
+            if !cur.is_invalid() {
+                path.push(cur);
+            }
+        }
+        path.reverse();
+        path
+    }
+
+    fn sibling_precedes(self, a: node, b: node) -> bool {
+        let mut cur = self.next(a.into_opt_idx().unwrap());
+        while let Some(idx) = cur.into_opt_idx() {
+            if cur == b {
+                return true;
+            }
+            cur = self.next(idx);
+        }
+        false
+    }
+
+    fn ancestors_or_self_contain(self, mut n: node, target: node) -> bool {
+        while let Some(idx) = n.into_opt_idx() {
+            if n == target {
+                return true;
+            }
+            n = self.parent(idx);
+        }
+        false
+    }
+
+    /// Like `seek_entry`, but stops climbing down at `max_depth`.
+    pub(crate) fn seek_entry_limited(
+        self,
+        (root, max_depth): (node, Option<usize>),
+        pos: SeekPos,
+    ) -> (node, usize) {
+        let mut cur = self.seek_entry(root, pos);
+        let mut depth = self.depth_of(root, cur);
+        if let Some(max_depth) = max_depth {
+            while depth > max_depth {
+                cur = self.parent(cur.into_opt_idx().unwrap());
+                depth -= 1;
+            }
+        }
+        (cur, depth)
+    }
+
+    pub(crate) fn navigate_entry(
+        self,
+        root: node,
+        cursor: node,
+        dir: NavigateDir,
+    ) -> Option<(node, bool)> {
+        let idx = self.live_idx(cursor)?;
+        if cursor == root && dir != NavigateDir::Down {
+            return None;
+        }
+        let (new_cursor, new_entry) = match dir {
+            NavigateDir::Up(up_entry) => (self.parent(idx).into_opt_node()?, up_entry),
+            NavigateDir::Down => (self.child_first(idx).into_opt_node()?, true),
+            NavigateDir::Left => (self.prev(idx).into_opt_node()?, true),
+            NavigateDir::Right => (self.next(idx).into_opt_node()?, true),
         };
-        Some((unsafe { node::new_with_idx(new_cursor_idx) }, new_entry))
+        Some((new_cursor, new_entry))
     }
 }
 
@@ -462,24 +1336,31 @@ impl<'a, T> Iterator for Iter<'a, T> {
     type Item = (IterMovement, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (movement, new_pair) = self.data
-            .iterate_entry(self.cursor, IterDir::Next, self.mode);
-        self.cursor = new_pair;
-
-        let valueref = unsafe { self.data.get_inner_ptr(new_pair.0).as_ref() }?;
-
+        let (movement, n) = self.state.next(self.data.raw())?;
+        let valueref = unsafe { self.data.raw().value_ptr(n).as_ref() }?;
         Some((movement, valueref))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.state.size_hint(self.data.raw())
+    }
 }
 
 impl<'a, T> BiIterator for Iter<'a, T> {
     fn prev(&mut self) -> Option<Self::Item> {
-        let (movement, new_pair) = self.data
-            .iterate_entry(self.cursor, IterDir::Prev, self.mode);
-        self.cursor = new_pair;
-
-        let valueref = unsafe { self.data.get_inner_ptr(new_pair.0).as_ref() }?;
+        let (movement, n) = self.state.prev(self.data.raw())?;
+        let valueref = unsafe { self.data.raw().value_ptr(n).as_ref() }?;
+        Some((movement, valueref))
+    }
+}
 
+/// Iterates from the back with a cursor of its own, independent from `prev`.
+///
+/// The movements are the ones `prev` would report.
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (movement, n) = self.state.next_back(self.data.raw())?;
+        let valueref = unsafe { self.data.raw().value_ptr(n).as_ref() }?;
         Some((movement, valueref))
     }
 }
@@ -488,30 +1369,13 @@ impl<'a, T> Navigator for Iter<'a, T> {
     type Item = &'a T;
 
     fn navigate(&mut self, dir: NavigateDir) -> Option<Self::Item> {
-        let new_pair = self.data.navigate_entry(self.cursor.0, dir)?;
-        self.cursor = new_pair;
-
-        let valueref = unsafe { self.data.get_inner_ptr(new_pair.0).as_ref() }?;
-        Some(valueref)
+        let n = self.state.navigate(self.data.raw(), dir)?;
+        unsafe { self.data.raw().value_ptr(n).as_ref() }
     }
 
     fn seek(&mut self, pos: SeekPos) -> Option<Self::Item> {
-        let new_pos = self.data.seek_entry(pos);
-        if new_pos.is_invalid() {
-            return None;
-        };
-        let new_entry = match self.mode {
-            IterMode::PreOrder => true,
-            IterMode::PostOrder => false,
-            IterMode::Both => match pos {
-                SeekPos::TopFirst | SeekPos::BottomFirst => true,
-                SeekPos::TopLast | SeekPos::BottomLast => false,
-            },
-        };
-        self.cursor = (new_pos, new_entry);
-
-        let valueref = unsafe { self.data.get_inner_ptr(new_pos).as_ref() }?;
-        Some(valueref)
+        let n = self.state.seek(self.data.raw(), pos)?;
+        unsafe { self.data.raw().value_ptr(n).as_ref() }
     }
 }
 
@@ -519,24 +1383,31 @@ impl<'a, T: 'a> Iterator for IterMut<'a, T> {
     type Item = (IterMovement, &'a mut T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (movement, new_pair) = self.data
-            .iterate_entry(self.cursor, IterDir::Next, self.mode);
-        self.cursor = new_pair;
-
-        let valueref = unsafe { self.data.get_inner_ptr_mut(new_pair.0).as_mut() }?;
-
+        let (movement, n) = self.state.next(self.data)?;
+        let valueref = unsafe { self.data.value_ptr_mut(n).as_mut() }?;
         Some((movement, valueref))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.state.size_hint(self.data)
+    }
 }
 
 impl<'a, T: 'a> BiIterator for IterMut<'a, T> {
     fn prev(&mut self) -> Option<Self::Item> {
-        let (movement, new_pair) = self.data
-            .iterate_entry(self.cursor, IterDir::Prev, self.mode);
-        self.cursor = new_pair;
-
-        let valueref = unsafe { self.data.get_inner_ptr_mut(new_pair.0).as_mut() }?;
+        let (movement, n) = self.state.prev(self.data)?;
+        let valueref = unsafe { self.data.value_ptr_mut(n).as_mut() }?;
+        Some((movement, valueref))
+    }
+}
 
+/// Iterates from the back with a cursor of its own, independent from `prev`.
+///
+/// The movements are the ones `prev` would report.
+impl<'a, T: 'a> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (movement, n) = self.state.next_back(self.data)?;
+        let valueref = unsafe { self.data.value_ptr_mut(n).as_mut() }?;
         Some((movement, valueref))
     }
 }
@@ -545,30 +1416,13 @@ impl<'a, T> Navigator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn navigate(&mut self, dir: NavigateDir) -> Option<Self::Item> {
-        let new_pair = self.data.navigate_entry(self.cursor.0, dir)?;
-        self.cursor = new_pair;
-
-        let valueref = unsafe { self.data.get_inner_ptr_mut(new_pair.0).as_mut() }?;
-        Some(valueref)
+        let n = self.state.navigate(self.data, dir)?;
+        unsafe { self.data.value_ptr_mut(n).as_mut() }
     }
 
     fn seek(&mut self, pos: SeekPos) -> Option<Self::Item> {
-        let new_pos = self.data.seek_entry(pos);
-        if new_pos.is_invalid() {
-            return None;
-        };
-        let new_entry = match self.mode {
-            IterMode::PreOrder => true,
-            IterMode::PostOrder => false,
-            IterMode::Both => match pos {
-                SeekPos::TopFirst | SeekPos::BottomFirst => true,
-                SeekPos::TopLast | SeekPos::BottomLast => false,
-            },
-        };
-        self.cursor = (new_pos, new_entry);
-
-        let valueref = unsafe { self.data.get_inner_ptr_mut(new_pos).as_mut() }?;
-        Some(valueref)
+        let n = self.state.seek(self.data, pos)?;
+        unsafe { self.data.value_ptr_mut(n).as_mut() }
     }
 }
 
@@ -584,8 +1438,20 @@ impl<V, T: Iterator<Item = (IterMovement, V)>> Iterator for Values<V, T> {
     fn next(&mut self) -> Option<V> {
         self.iter.next().map(|x| x.1)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
+impl<V, T: DoubleEndedIterator<Item = (IterMovement, V)>> DoubleEndedIterator for Values<V, T> {
+    fn next_back(&mut self) -> Option<V> {
+        self.iter.next_back().map(|x| x.1)
+    }
+}
+
+impl<V, T: ExactSizeIterator<Item = (IterMovement, V)>> ExactSizeIterator for Values<V, T> {}
+
 impl<V, T: BiIterator<Item = (IterMovement, V)>> BiIterator for Values<V, T> {
     fn prev(&mut self) -> Option<V> {
         self.iter.prev().map(|x| x.1)
@@ -603,3 +1469,406 @@ impl<V, T: Navigator<Item = V>> Navigator for Values<V, T> {
         self.iter.seek(pos)
     }
 }
+
+impl<'a, T> Values<&'a T, Iter<'a, T>> {
+    /// Reports the depth and the node of each visit along with the borrowed value.
+    pub fn with_depth(self) -> WithDepth<&'a T, Iter<'a, T>> {
+        self.iter.with_depth()
+    }
+}
+
+impl<'a, T> Values<&'a mut T, IterMut<'a, T>> {
+    /// Reports the depth and the node of each visit along with the borrowed value.
+    pub fn with_depth(self) -> WithDepth<&'a mut T, IterMut<'a, T>> {
+        self.iter.with_depth()
+    }
+}
+
+impl<V, T: LastVisit> LastVisit for Values<V, T> {
+    fn last_visit(&self) -> Option<IterEvent<node>> {
+        self.iter.last_visit()
+    }
+
+    fn last_depth(&self) -> usize {
+        self.iter.last_depth()
+    }
+
+    fn last_visit_back(&self) -> Option<IterEvent<node>> {
+        self.iter.last_visit_back()
+    }
+
+    fn last_depth_back(&self) -> usize {
+        self.iter.last_depth_back()
+    }
+}
+
+/// Helper iterator that knows how many visits are left, see `Iter::exact`.
+#[derive(Clone)]
+pub struct ExactIter<I> {
+    iter: I,
+}
+
+impl<I> ExactIter<I> {
+    pub fn values<V>(self) -> Values<V, Self>
+    where
+        Self: Iterator<Item = (IterMovement, V)>,
+    {
+        Values {
+            iter: self,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for ExactIter<I> {
+    type Item = I::Item;
+    fn next(&mut self) -> Option<I::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for ExactIter<I> {
+    fn next_back(&mut self) -> Option<I::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<I: Iterator> ExactSizeIterator for ExactIter<I> {}
+
+impl<I: BiIterator> BiIterator for ExactIter<I> {
+    fn prev(&mut self) -> Option<I::Item> {
+        self.iter.prev()
+    }
+}
+
+impl<I: LastVisit> LastVisit for ExactIter<I> {
+    fn last_visit(&self) -> Option<IterEvent<node>> {
+        self.iter.last_visit()
+    }
+
+    fn last_depth(&self) -> usize {
+        self.iter.last_depth()
+    }
+
+    fn last_visit_back(&self) -> Option<IterEvent<node>> {
+        self.iter.last_visit_back()
+    }
+
+    fn last_depth_back(&self) -> usize {
+        self.iter.last_depth_back()
+    }
+}
+
+/// Whether a visit of a node happens before or after its descendants.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IterEvent<V> {
+    Enter(V),
+    Leave(V),
+}
+
+impl<V> IterEvent<V> {
+    fn new(v: V, entry: bool) -> Self {
+        if entry {
+            IterEvent::Enter(v)
+        } else {
+            IterEvent::Leave(v)
+        }
+    }
+
+    pub fn is_enter(&self) -> bool {
+        matches!(*self, IterEvent::Enter(_))
+    }
+
+    pub fn is_leave(&self) -> bool {
+        matches!(*self, IterEvent::Leave(_))
+    }
+
+    pub fn value(self) -> V {
+        match self {
+            IterEvent::Enter(v) | IterEvent::Leave(v) => v,
+        }
+    }
+
+    pub fn map<U, F: FnOnce(V) -> U>(self, f: F) -> IterEvent<U> {
+        match self {
+            IterEvent::Enter(v) => IterEvent::Enter(f(v)),
+            IterEvent::Leave(v) => IterEvent::Leave(f(v)),
+        }
+    }
+}
+
+/// Helper iterator that returns the visited node, the movement and the borrowed node value.
+#[derive(Clone)]
+pub struct WithNodes<V, T> {
+    iter: T,
+    phantom: PhantomData<V>,
+}
+
+impl<V, T: Iterator<Item = (IterMovement, V)> + LastVisit> Iterator for WithNodes<V, T> {
+    type Item = (node, IterMovement, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (movement, v) = self.iter.next()?;
+        let visit = self.iter.last_visit()?;
+        Some((visit.value(), movement, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<V, T> DoubleEndedIterator for WithNodes<V, T>
+where
+    T: DoubleEndedIterator<Item = (IterMovement, V)> + LastVisit,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (movement, v) = self.iter.next_back()?;
+        let visit = self.iter.last_visit_back()?;
+        Some((visit.value(), movement, v))
+    }
+}
+
+impl<V, T: BiIterator<Item = (IterMovement, V)> + LastVisit> BiIterator for WithNodes<V, T> {
+    fn prev(&mut self) -> Option<Self::Item> {
+        let (movement, v) = self.iter.prev()?;
+        let visit = self.iter.last_visit()?;
+        Some((visit.value(), movement, v))
+    }
+}
+
+/// Helper iterator that returns only the visited node.
+#[derive(Clone)]
+pub struct Nodes<V, T> {
+    iter: T,
+    phantom: PhantomData<V>,
+}
+
+impl<V, T: Iterator<Item = (IterMovement, V)> + LastVisit> Iterator for Nodes<V, T> {
+    type Item = node;
+    fn next(&mut self) -> Option<node> {
+        self.iter.next()?;
+        self.iter.last_visit().map(IterEvent::value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<V, T: DoubleEndedIterator<Item = (IterMovement, V)> + LastVisit> DoubleEndedIterator
+    for Nodes<V, T>
+{
+    fn next_back(&mut self) -> Option<node> {
+        self.iter.next_back()?;
+        self.iter.last_visit_back().map(IterEvent::value)
+    }
+}
+
+impl<V, T: BiIterator<Item = (IterMovement, V)> + LastVisit> BiIterator for Nodes<V, T> {
+    fn prev(&mut self) -> Option<node> {
+        self.iter.prev()?;
+        self.iter.last_visit().map(IterEvent::value)
+    }
+}
+
+/// Forest iterators that can tell which node they visited last.
+pub trait LastVisit {
+    /// Returns the node the iterator is on, and whether it was entered or left.
+    fn last_visit(&self) -> Option<IterEvent<node>>;
+
+    /// Returns the depth of the node the iterator is on.
+    fn last_depth(&self) -> usize;
+
+    /// Like `last_visit`, for the cursor that `next_back` moves.
+    fn last_visit_back(&self) -> Option<IterEvent<node>>;
+
+    /// Like `last_depth`, for the cursor that `next_back` moves.
+    fn last_depth_back(&self) -> usize;
+}
+
+/// Helper iterator that returns the visited node and the borrowed node value,
+/// tagged with whether the node is being entered or left.
+#[derive(Clone)]
+pub struct Events<V, T> {
+    iter: T,
+    phantom: PhantomData<V>,
+}
+
+impl<V, T: Iterator<Item = (IterMovement, V)> + LastVisit> Iterator for Events<V, T> {
+    type Item = (node, IterEvent<V>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, v) = self.iter.next()?;
+        let visit = self.iter.last_visit()?;
+        Some((visit.value(), visit.map(|_| v)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<V, T: DoubleEndedIterator<Item = (IterMovement, V)> + LastVisit> DoubleEndedIterator
+    for Events<V, T>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_, v) = self.iter.next_back()?;
+        let visit = self.iter.last_visit_back()?;
+        Some((visit.value(), visit.map(|_| v)))
+    }
+}
+
+impl<V, T: BiIterator<Item = (IterMovement, V)> + LastVisit> BiIterator for Events<V, T> {
+    fn prev(&mut self) -> Option<Self::Item> {
+        let (_, v) = self.iter.prev()?;
+        let visit = self.iter.last_visit()?;
+        Some((visit.value(), visit.map(|_| v)))
+    }
+}
+
+/// A visit reported by `IterMut::events`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum IterEventMut<'a, T: 'a> {
+    Enter(node, &'a mut T),
+    Leave(node),
+}
+
+impl<'a, T> IterEventMut<'a, T> {
+    pub fn node(&self) -> node {
+        match *self {
+            IterEventMut::Enter(n, _) | IterEventMut::Leave(n) => n,
+        }
+    }
+
+    pub fn is_enter(&self) -> bool {
+        matches!(*self, IterEventMut::Enter(..))
+    }
+
+    pub fn is_leave(&self) -> bool {
+        matches!(*self, IterEventMut::Leave(_))
+    }
+}
+
+/// Helper iterator that returns the entering and leaving events of a mutable iterator.
+pub struct EventsMut<'a, T: 'a> {
+    iter: IterMut<'a, T>,
+}
+
+impl<'a, T> EventsMut<'a, T> {
+    fn event(&self, (n, entry): (node, bool)) -> IterEventMut<'a, T> {
+        if entry {
+            // each node is entered once, so the value is lent once.
+            let valueref = unsafe { &mut *self.iter.data.value_ptr_mut(n) };
+            IterEventMut::Enter(n, valueref)
+        } else {
+            IterEventMut::Leave(n)
+        }
+    }
+}
+
+impl<'a, T> Iterator for EventsMut<'a, T> {
+    type Item = IterEventMut<'a, T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.state.next(self.iter.data)?;
+        let cursor = self.iter.state.cursor;
+        Some(self.event(cursor))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for EventsMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.state.next_back(self.iter.data)?;
+        let back = self.iter.state.back;
+        Some(self.event(back))
+    }
+}
+
+/// Helper iterator that returns the depth of the visited node, the node and the borrowed node value.
+///
+/// Top-level nodes, or the subtree root for subtree iterators, have depth 0.
+#[derive(Clone)]
+pub struct WithDepth<V, T> {
+    iter: T,
+    phantom: PhantomData<V>,
+}
+
+impl<V, T: Iterator<Item = (IterMovement, V)> + LastVisit> Iterator for WithDepth<V, T> {
+    type Item = (usize, node, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, v) = self.iter.next()?;
+        let visit = self.iter.last_visit()?;
+        Some((self.iter.last_depth(), visit.value(), v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<V, T: DoubleEndedIterator<Item = (IterMovement, V)> + LastVisit> DoubleEndedIterator
+    for WithDepth<V, T>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_, v) = self.iter.next_back()?;
+        let visit = self.iter.last_visit_back()?;
+        Some((self.iter.last_depth_back(), visit.value(), v))
+    }
+}
+
+impl<V, T: BiIterator<Item = (IterMovement, V)> + LastVisit> BiIterator for WithDepth<V, T> {
+    fn prev(&mut self) -> Option<Self::Item> {
+        let (_, v) = self.iter.prev()?;
+        let visit = self.iter.last_visit()?;
+        Some((self.iter.last_depth(), visit.value(), v))
+    }
+}
+
+/// Helper iterator that skips the descendants of the nodes matching a predicate.
+///
+/// The matching nodes themselves are still visited.
+#[derive(Clone)]
+pub struct PruneWhere<I, P> {
+    iter: I,
+    pred: P,
+}
+
+impl<I, P> PruneWhere<I, P> {
+    pub fn values<V>(self) -> Values<V, Self>
+    where
+        Self: Iterator<Item = (IterMovement, V)>,
+    {
+        Values {
+            iter: self,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, P: FnMut(&T) -> bool> Iterator for PruneWhere<Iter<'a, T>, P> {
+    type Item = (IterMovement, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (movement, v) = self.iter.next()?;
+        if self.iter.state.cursor.1 && (self.pred)(v) {
+            self.iter.skip_subtree();
+        }
+        Some((movement, v))
+    }
+}
+
+impl<'a, T, P: FnMut(&T) -> bool> Iterator for PruneWhere<IterMut<'a, T>, P> {
+    type Item = (IterMovement, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (movement, v) = self.iter.next()?;
+        if self.iter.state.cursor.1 && (self.pred)(v) {
+            self.iter.skip_subtree();
+        }
+        Some((movement, v))
+    }
+}
@@ -0,0 +1,167 @@
+use super::collections::*;
+use super::error::*;
+use super::primitive::*;
+use std::iter::FromIterator;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ReplayOrder {
+    PreOrder,
+    PostOrder,
+}
+
+/// Rebuilds a forest from the movements of a pre-order or post-order walk.
+///
+/// Until a movement only one of the orders can produce shows up, the stream
+/// consists of leaf roots only, which both orders describe the same way.
+struct MovementReplay<T> {
+    forest: Forest<T>,
+    order: Option<ReplayOrder>,
+    started: bool,
+    /// Pre-order: the nodes from the current root down to the last created node.
+    path: Vec<node>,
+    /// Post-order: for each level below the top, the finished nodes still waiting for their parent.
+    pending: Vec<Vec<node>>,
+}
+
+impl<T> MovementReplay<T> {
+    fn new() -> Self {
+        MovementReplay {
+            forest: Forest::new(),
+            order: None,
+            started: false,
+            path: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn settle_order(&mut self, order: ReplayOrder) -> Result<(), ForestError> {
+        match self.order {
+            Some(cur_order) if cur_order != order => Err(ForestError::InvalidMovement),
+            _ => {
+                self.order = Some(order);
+                Ok(())
+            }
+        }
+    }
+
+    fn feed(&mut self, movement: IterMovement, v: T) -> Result<(), ForestError> {
+        if !self.started {
+            self.started = true;
+            return match movement {
+                IterMovement::DownFirst(0) => {
+                    let new_node = self.forest.create_node(v);
+                    self.path.push(new_node);
+                    Ok(())
+                }
+                IterMovement::DownFirst(depth) => {
+                    self.settle_order(ReplayOrder::PostOrder)?;
+                    self.push_pending(depth, v);
+                    Ok(())
+                }
+                _ => Err(ForestError::InvalidMovement),
+            };
+        }
+
+        match movement {
+            IterMovement::Right => {
+                if self.pending.is_empty() {
+                    self.place_after_path(v);
+                } else {
+                    self.push_pending(0, v);
+                }
+                Ok(())
+            }
+            IterMovement::DownFirst(1) => {
+                self.settle_order(ReplayOrder::PreOrder)?;
+                let parent = *self.path.last().unwrap();
+                let new_node = self.forest.create_node(v);
+                let _ = self.forest.append_node_child(parent, new_node);
+                self.path.push(new_node);
+                Ok(())
+            }
+            IterMovement::UpNRight(levels) => {
+                self.settle_order(ReplayOrder::PreOrder)?;
+                if levels == 0 || levels >= self.path.len() {
+                    return Err(ForestError::InvalidMovement);
+                }
+                let new_len = self.path.len() - levels;
+                self.path.truncate(new_len);
+                self.place_after_path(v);
+                Ok(())
+            }
+            IterMovement::RightDownFirstN(depth) if depth > 0 => {
+                self.settle_order(ReplayOrder::PostOrder)?;
+                self.push_pending(depth, v);
+                Ok(())
+            }
+            IterMovement::Up(1) => {
+                self.settle_order(ReplayOrder::PostOrder)?;
+                let children = self.pending.pop().ok_or(ForestError::InvalidMovement)?;
+                let new_node = self.forest.create_node(v);
+                for child in children {
+                    let _ = self.forest.append_node_child(new_node, child);
+                }
+                if let Some(siblings) = self.pending.last_mut() {
+                    siblings.push(new_node);
+                }
+                Ok(())
+            }
+            _ => Err(ForestError::InvalidMovement),
+        }
+    }
+
+    /// Creates a node as the next sibling of the last node on the path.
+    fn place_after_path(&mut self, v: T) {
+        let new_node = self.forest.create_node(v);
+        let path_len = self.path.len();
+        if path_len >= 2 {
+            let parent = self.path[path_len - 2];
+            let _ = self.forest.append_node_child(parent, new_node);
+        }
+        match self.path.last_mut() {
+            Some(last) => *last = new_node,
+            None => self.path.push(new_node),
+        }
+    }
+
+    /// Descends `depth` new levels, and creates a finished node at the bottom.
+    fn push_pending(&mut self, depth: usize, v: T) {
+        for _ in 0..depth {
+            self.pending.push(Vec::new());
+        }
+        let new_node = self.forest.create_node(v);
+        self.pending.last_mut().unwrap().push(new_node);
+    }
+
+    fn finish(self) -> Result<Forest<T>, ForestError> {
+        if self.pending.is_empty() {
+            Ok(self.forest)
+        } else {
+            Err(ForestError::InvalidMovement)
+        }
+    }
+}
+
+impl<T> Forest<T> {
+    /// Builds a forest from the items of a pre-order or post-order `Iter`,
+    /// with the borrowed values replaced by owned ones.
+    pub fn from_movements<I>(iter: I) -> Result<Self, ForestError>
+    where
+        I: IntoIterator<Item = (IterMovement, T)>,
+    {
+        let mut replay = MovementReplay::new();
+        for (movement, v) in iter {
+            replay.feed(movement, v)?;
+        }
+        replay.finish()
+    }
+}
+
+/// # Panics
+///
+/// Panics if the movements don't describe a forest. Use `Forest::from_movements` to handle that case.
+impl<T> FromIterator<(IterMovement, T)> for Forest<T> {
+    fn from_iter<I: IntoIterator<Item = (IterMovement, T)>>(iter: I) -> Self {
+        Forest::from_movements(iter).expect("malformed movement stream")
+    }
+}
@@ -0,0 +1,166 @@
+use super::collections::*;
+use super::error::*;
+use super::primitive::*;
+use std::collections::HashMap;
+use std::iter;
+
+impl<T: Clone> Forest<T> {
+    /// Copies `n` and its descendants into a new top-level tree, and returns its root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a live node of this forest.
+    pub fn clone_subtree(&mut self, n: node) -> node {
+        let copies = snapshot_subtree(self, n);
+        self.build_subtree(copies).0
+    }
+
+    /// Copies `n` and its descendants from `other` into a new top-level tree.
+    ///
+    /// Returns the root of the copy, and a map from the nodes of `other` to their copies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a live node of `other`.
+    pub fn import_subtree(&mut self, other: &forest<T>, n: node) -> (node, HashMap<node, node>) {
+        let copies = snapshot_subtree(other, n);
+        self.build_subtree(copies)
+    }
+}
+
+impl<T> Forest<T> {
+    /// Moves `n` and its descendants out of `other` into a new top-level tree.
+    ///
+    /// Returns the root of the moved tree, and a map from the nodes of `other` to their new nodes.
+    /// Fails with `InvalidNode` if `n` is not a live node of `other`.
+    pub fn transplant_from(
+        &mut self,
+        other: &mut Forest<T>,
+        n: node,
+    ) -> Result<(node, HashMap<node, node>), ForestError> {
+        other.node_idx(n)?;
+        let items = other.take_subtree(n);
+        Ok(self.build_subtree(items))
+    }
+
+    /// Moves all trees of `fragment` under `parent`, after its existing children.
+    ///
+    /// Returns the new handles of the fragment's top-level nodes, in order. Fails with
+    /// `InvalidNode` if `parent` is not a live node of this forest, and so do the other
+    /// grafting methods for their referents.
+    pub fn append_forest(
+        &mut self,
+        parent: node,
+        fragment: Forest<T>,
+    ) -> Result<Vec<node>, ForestError> {
+        let new_prev = self.live_entry(parent)?.child_last;
+        Ok(self.graft_forest(parent, new_prev, node::new_invalid(), fragment))
+    }
+
+    /// Moves all trees of `fragment` under `parent`, before its existing children.
+    pub fn prepend_forest(
+        &mut self,
+        parent: node,
+        fragment: Forest<T>,
+    ) -> Result<Vec<node>, ForestError> {
+        let new_next = self.live_entry(parent)?.child_first;
+        Ok(self.graft_forest(parent, node::new_invalid(), new_next, fragment))
+    }
+
+    /// Moves all trees of `fragment` right before `referent`, as its siblings.
+    pub fn insert_forest_before(
+        &mut self,
+        referent: node,
+        fragment: Forest<T>,
+    ) -> Result<Vec<node>, ForestError> {
+        let (parent, new_prev) = {
+            let entry = self.live_entry(referent)?;
+            (entry.parent, entry.prev)
+        };
+        Ok(self.graft_forest(parent, new_prev, referent, fragment))
+    }
+
+    /// Moves all trees of `fragment` right after `referent`, as its siblings.
+    pub fn insert_forest_after(
+        &mut self,
+        referent: node,
+        fragment: Forest<T>,
+    ) -> Result<Vec<node>, ForestError> {
+        let (parent, new_next) = {
+            let entry = self.live_entry(referent)?;
+            (entry.parent, entry.next)
+        };
+        Ok(self.graft_forest(parent, referent, new_next, fragment))
+    }
+
+    /// Moves `n` and its descendants out into a new compact forest.
+    ///
+    /// Returns the new forest, and a map from the moved nodes to their handles there.
+    /// Fails with `InvalidNode` if `n` is not a live node of this forest.
+    pub fn split_off(&mut self, n: node) -> Result<(Forest<T>, HashMap<node, node>), ForestError> {
+        self.node_idx(n)?;
+        Ok(self.split_off_live(n))
+    }
+
+    /// Splits the forest into one compact forest per top-level tree, in order.
+    ///
+    /// Each forest comes with a map from the nodes it took over to their handles there.
+    pub fn into_trees(mut self) -> Vec<(Forest<T>, HashMap<node, node>)> {
+        let mut trees = Vec::new();
+        while let Some(root) = self.get_first_root_node() {
+            trees.push(self.split_off_live(root));
+        }
+        trees
+    }
+
+    /// Like `split_off`, for a node known to be live.
+    fn split_off_live(&mut self, n: node) -> (Forest<T>, HashMap<node, node>) {
+        let mut tree = self.new_sibling_forest();
+        let items = self.take_subtree(n);
+        let (_, mapping) = tree.build_subtree(items);
+        (tree, mapping)
+    }
+
+    /// Creates an empty forest, with an identity of its own if this forest has one.
+    fn new_sibling_forest(&self) -> Forest<T> {
+        if self.forest_id() == ANONYMOUS_FOREST_ID {
+            Forest::new()
+        } else {
+            Forest::with_identity()
+        }
+    }
+
+    /// Creates a new top-level tree from `(node, parent, value)` items in pre-order,
+    /// where the first item is the root and the nodes belong to some other tree.
+    fn build_subtree<I>(&mut self, items: I) -> (node, HashMap<node, node>)
+    where
+        I: IntoIterator<Item = (node, Option<node>, T)>,
+    {
+        let mut mapping = HashMap::new();
+        let mut new_root = node::new_invalid();
+        for (old_node, old_parent, v) in items {
+            let new_parent = if new_root.is_invalid() {
+                node::new_invalid()
+            } else {
+                mapping[&old_parent.unwrap()]
+            };
+            let new_node = self.create_child_node(new_parent, v);
+            if new_root.is_invalid() {
+                new_root = new_node;
+            }
+            mapping.insert(old_node, new_node);
+        }
+        (new_root, mapping)
+    }
+}
+
+/// Returns `n` and its descendants in pre-order, along with their parents and cloned values.
+fn snapshot_subtree<T: Clone>(src: &forest<T>, n: node) -> Vec<(node, Option<node>, T)> {
+    iter::once(n)
+        .chain(src.descendants(n))
+        .map(|cur| {
+            let parent = src.raw().parent(cur.into_opt_idx().unwrap()).into_opt_node();
+            (cur, parent, src[cur].clone())
+        })
+        .collect()
+}
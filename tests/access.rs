@@ -0,0 +1,51 @@
+extern crate forests;
+use forests::*;
+
+#[test]
+fn access_forest_001() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    forest.append_node_child(node1, node2);
+
+    assert_eq!(forest.get(node1), Some(&1usize));
+    assert_eq!(forest[node2], 2usize);
+    *forest.get_mut(node1).unwrap() = 3usize;
+    forest[node2] += 1;
+    assert_eq!(forest[node1], 3usize);
+    assert_eq!(forest[node2], 3usize);
+
+    forest.remove_node(node2);
+    assert_eq!(forest.get(node2), None);
+    assert_eq!(forest.get_mut(node2), None);
+}
+
+#[test]
+fn access_forest_002() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    let node3 = forest.create_node(3usize);
+
+    {
+        let [a, b, c] = forest.get_many_mut([node3, node1, node2]).unwrap();
+        ::std::mem::swap(a, b);
+        *c += 10;
+    }
+    assert_eq!(forest[node1], 3usize);
+    assert_eq!(forest[node2], 12usize);
+    assert_eq!(forest[node3], 1usize);
+
+    assert!(forest.get_many_mut([node1, node2, node1]).is_none());
+    forest.remove_node(node3);
+    assert!(forest.get_many_mut([node1, node3]).is_none());
+}
+
+#[test]
+#[should_panic]
+fn access_forest_003() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    forest.remove_node(node1);
+    let _ = forest[node1];
+}
@@ -0,0 +1,107 @@
+extern crate forests;
+use forests::*;
+
+mod common;
+
+#[test]
+fn aliasing_forest_001() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let refs: Vec<&mut usize> = forest.iter_mut().values().collect();
+    assert_eq!(refs.len(), 6);
+    for r in refs.into_iter().rev() {
+        *r += 10;
+    }
+
+    let mut refs: Vec<&mut usize> = forest.iter_mut().mode(IterMode::PostOrder).values().collect();
+    for idx in (0..refs.len()).rev() {
+        *refs[idx] = idx;
+    }
+
+    let expected_output = vec![3usize, 0, 2, 1, 5, 4];
+    let actual_output: Vec<_> = nodes.iter().map(|&n| forest[n]).collect();
+    assert_eq!(expected_output, actual_output);
+}
+
+#[test]
+fn aliasing_forest_002() {
+    let (mut forest, nodes) = common::small_forest();
+
+    {
+        let mut iter = forest.iter_mut();
+        let a = iter.seek(SeekPos::TopFirst).unwrap();
+        let b = iter.down().unwrap();
+        let c = iter.right().unwrap();
+        let d = iter.down().unwrap();
+        *c += 100;
+        *a += 100;
+        *d += 100;
+        *b += 100;
+        let e = iter.seek(SeekPos::BottomLast).unwrap();
+        *e += 100;
+        *a += 1;
+        let f = iter.up().unwrap();
+        *f += 100;
+        *c += 1;
+    }
+
+    let expected_output = vec![101usize, 101, 103, 103, 104, 105];
+    let actual_output: Vec<_> = nodes.iter().map(|&n| forest[n]).collect();
+    assert_eq!(expected_output, actual_output);
+}
+
+#[test]
+fn aliasing_forest_003() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter_mut().mode(IterMode::PostOrder);
+    let (_, first) = iter.next().unwrap();
+    let rest: Vec<_> = iter.values().collect();
+    for r in rest {
+        *r += *first;
+        *first += 1;
+    }
+
+    let expected_output = vec![3usize, 6, 4, 4, 9, 9];
+    let actual_output: Vec<_> = nodes.iter().map(|&n| forest[n]).collect();
+    assert_eq!(expected_output, actual_output);
+}
+
+#[test]
+fn aliasing_forest_004() {
+    let (forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter();
+    let a = iter.seek(SeekPos::TopLast).unwrap();
+    let b = iter.down().unwrap();
+    let all: Vec<_> = forest.iter().values().collect();
+    let c = forest.get(nodes[0]).unwrap();
+    assert_eq!((*a, *b, *c, all.len()), (4, 5, 0, 6));
+}
+
+#[test]
+fn aliasing_forest_005() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let events: Vec<_> = forest.iter_mut().events().collect();
+    assert_eq!(events.len(), 12);
+    let mut leaves = Vec::new();
+    let mut values = Vec::new();
+    for event in events {
+        match event {
+            IterEventMut::Enter(_, v) => values.push(v),
+            IterEventMut::Leave(n) => leaves.push(n),
+        }
+    }
+    for v in values.iter_mut().rev() {
+        **v += 10;
+    }
+    for v in values {
+        *v += 10;
+    }
+
+    assert_eq!(leaves, vec![nodes[1], nodes[3], nodes[2], nodes[0], nodes[5], nodes[4]]);
+    let expected_output = vec![20usize, 21, 22, 23, 24, 25];
+    let actual_output: Vec<_> = nodes.iter().map(|&n| forest[n]).collect();
+    assert_eq!(expected_output, actual_output);
+}
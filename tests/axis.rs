@@ -0,0 +1,140 @@
+extern crate forests;
+use forests::*;
+
+fn build_forest() -> (Forest<usize>, Vec<node>) {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..10usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[1], nodes[2]);
+    forest.append_node_child(nodes[1], nodes[3]);
+    forest.append_node_child(nodes[0], nodes[4]);
+    forest.append_node_child(nodes[4], nodes[5]);
+    forest.append_node_child(nodes[6], nodes[7]);
+    forest.append_node_child(nodes[6], nodes[8]);
+    forest.append_node_child(nodes[8], nodes[9]);
+    (forest, nodes)
+}
+
+fn values<I: Iterator<Item = node>>(forest: &Forest<usize>, iter: I) -> Vec<usize> {
+    iter.map(|n| forest[n]).collect()
+}
+
+#[test]
+fn axis_forest_001() {
+    let (forest, nodes) = build_forest();
+
+    assert_eq!(values(&forest, forest.children(nodes[0])), vec![1, 4]);
+    assert_eq!(values(&forest, forest.children_rev(nodes[0])), vec![4, 1]);
+    assert_eq!(values(&forest, forest.children(nodes[2])), vec![]);
+    assert_eq!(values(&forest, forest.following_siblings(nodes[2])), vec![3]);
+    assert_eq!(values(&forest, forest.following_siblings(nodes[3])), vec![]);
+    assert_eq!(values(&forest, forest.following_siblings(nodes[0])), vec![6]);
+    assert_eq!(values(&forest, forest.preceding_siblings(nodes[6])), vec![0]);
+    assert_eq!(values(&forest, forest.preceding_siblings(nodes[8])), vec![7]);
+    assert_eq!(values(&forest, forest.preceding_siblings(nodes[7])), vec![]);
+
+    let mut children = forest.children(nodes[6]);
+    assert_eq!(children.next_back(), Some(nodes[8]));
+    assert_eq!(children.next(), Some(nodes[7]));
+    assert_eq!(children.next(), None);
+    assert_eq!(children.next_back(), None);
+}
+
+#[test]
+fn axis_forest_002() {
+    let (forest, nodes) = build_forest();
+
+    assert_eq!(values(&forest, forest.ancestors(nodes[3])), vec![1, 0]);
+    assert_eq!(values(&forest, forest.ancestors_or_self(nodes[3])), vec![3, 1, 0]);
+    assert_eq!(values(&forest, forest.ancestors(nodes[6])), vec![]);
+
+    assert_eq!(values(&forest, forest.descendants(nodes[0])), vec![1, 2, 3, 4, 5]);
+    assert_eq!(values(&forest, forest.descendants(nodes[0]).rev()), vec![5, 4, 3, 2, 1]);
+    assert_eq!(values(&forest, forest.descendants(nodes[5])), vec![]);
+
+    let mut descendants = forest.descendants(nodes[0]);
+    assert_eq!(descendants.next(), Some(nodes[1]));
+    assert_eq!(descendants.next_back(), Some(nodes[5]));
+    assert_eq!(descendants.next_back(), Some(nodes[4]));
+    assert_eq!(descendants.next(), Some(nodes[2]));
+    assert_eq!(descendants.next(), Some(nodes[3]));
+    assert_eq!(descendants.next(), None);
+    assert_eq!(descendants.next_back(), None);
+}
+
+#[test]
+fn axis_forest_003() {
+    let (forest, nodes) = build_forest();
+
+    assert_eq!(values(&forest, forest.following(nodes[1])), vec![4, 5, 6, 7, 8, 9]);
+    assert_eq!(values(&forest, forest.following(nodes[3])), vec![4, 5, 6, 7, 8, 9]);
+    assert_eq!(values(&forest, forest.following(nodes[7]).rev()), vec![9, 8]);
+    assert_eq!(values(&forest, forest.following(nodes[6])), vec![]);
+    assert_eq!(values(&forest, forest.following(nodes[9])), vec![]);
+
+    assert_eq!(values(&forest, forest.preceding(nodes[9])), vec![7, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(values(&forest, forest.preceding(nodes[4])), vec![3, 2, 1]);
+    assert_eq!(values(&forest, forest.preceding(nodes[6])), vec![5, 4, 3, 2, 1, 0]);
+    assert_eq!(values(&forest, forest.preceding(nodes[2])), vec![]);
+    assert_eq!(values(&forest, forest.preceding(nodes[0])), vec![]);
+}
+
+#[test]
+fn axis_forest_004() {
+    let (forest, nodes) = build_forest();
+
+    let level_order: Vec<_> = forest
+        .level_order()
+        .map(|(depth, n)| (depth, forest[n]))
+        .collect();
+    assert_eq!(
+        level_order,
+        vec![(0, 0), (0, 6), (1, 1), (1, 4), (1, 7), (1, 8), (2, 2), (2, 3), (2, 5), (2, 9)]
+    );
+
+    let level_order: Vec<_> = forest
+        .level_order_subtree(nodes[0])
+        .map(|(depth, n)| (depth, forest[n]))
+        .collect();
+    assert_eq!(level_order, vec![(0, 0), (1, 1), (1, 4), (2, 2), (2, 3), (2, 5)]);
+    assert_eq!(forest.level_order_subtree(nodes[9]).collect::<Vec<_>>(), vec![(0, nodes[9])]);
+
+    assert_eq!(values(&forest, forest.level(nodes[0], 0)), vec![0]);
+    assert_eq!(values(&forest, forest.level(nodes[0], 1)), vec![1, 4]);
+    assert_eq!(values(&forest, forest.level(nodes[0], 2)), vec![2, 3, 5]);
+    assert_eq!(values(&forest, forest.level(nodes[0], 3)), vec![]);
+    assert_eq!(values(&forest, forest.level(nodes[6], 2)), vec![9]);
+    assert_eq!(values(&forest, forest.level(nodes[7], 1)), vec![]);
+}
+
+#[test]
+fn axis_forest_005() {
+    let (forest, nodes) = build_forest();
+
+    assert_eq!(values(&forest, forest.ancestors(nodes[9]).rev()), vec![6, 8]);
+    assert_eq!(values(&forest, forest.ancestors_or_self(nodes[3]).rev()), vec![0, 1, 3]);
+    assert_eq!(values(&forest, forest.ancestors(nodes[0]).rev()), vec![]);
+
+    let mut ancestors = forest.ancestors_or_self(nodes[2]);
+    assert_eq!(ancestors.next_back(), Some(nodes[0]));
+    assert_eq!(ancestors.next(), Some(nodes[2]));
+    assert_eq!(ancestors.next_back(), Some(nodes[1]));
+    assert_eq!(ancestors.next(), None);
+    assert_eq!(ancestors.next_back(), None);
+
+    assert_eq!(values(&forest, forest.preceding(nodes[9]).rev()), vec![0, 1, 2, 3, 4, 5, 7]);
+    assert_eq!(values(&forest, forest.preceding(nodes[5]).rev()), vec![1, 2, 3]);
+    assert_eq!(values(&forest, forest.preceding(nodes[6]).rev()), vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(values(&forest, forest.preceding(nodes[2]).rev()), vec![]);
+
+    let mut preceding = forest.preceding(nodes[9]);
+    assert_eq!(preceding.next(), Some(nodes[7]));
+    assert_eq!(preceding.next_back(), Some(nodes[0]));
+    assert_eq!(preceding.next_back(), Some(nodes[1]));
+    assert_eq!(preceding.next(), Some(nodes[5]));
+    assert_eq!(preceding.next(), Some(nodes[4]));
+    assert_eq!(preceding.next_back(), Some(nodes[2]));
+    assert_eq!(preceding.next(), Some(nodes[3]));
+    assert_eq!(preceding.next(), None);
+    assert_eq!(preceding.next_back(), None);
+}
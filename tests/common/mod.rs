@@ -0,0 +1,12 @@
+use forests::*;
+
+/// Returns the forest `0(1, 2(3)), 4(5)`, along with its nodes in value order.
+pub fn small_forest() -> (Forest<usize>, Vec<node>) {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..6usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[0], nodes[2]);
+    forest.append_node_child(nodes[2], nodes[3]);
+    forest.append_node_child(nodes[4], nodes[5]);
+    (forest, nodes)
+}
@@ -0,0 +1,96 @@
+extern crate forests;
+use forests::*;
+
+fn render(forest: &Forest<&'static str>) -> String {
+    let mut output = String::new();
+    for (_, event) in forest.iter().events() {
+        match event {
+            IterEvent::Enter(tag) => output += &format!("<{}>", tag),
+            IterEvent::Leave(tag) => output += &format!("</{}>", tag),
+        }
+    }
+    output
+}
+
+#[test]
+fn cursor_forest_001() {
+    let mut forest: Forest<&'static str> = Forest::new();
+    let html = forest.create_node("html");
+    let body = forest.create_node("body");
+    forest.append_node_child(html, body);
+
+    let mut cursor = forest.cursor_mut(html);
+    assert_eq!(cursor.up(), None);
+    assert_eq!(cursor.right(), None);
+    assert_eq!(cursor.down(), Some(body));
+    assert_eq!(cursor.node(), body);
+    assert_eq!(*cursor.value(), "body");
+
+    let p = cursor.insert_child_first("p");
+    let head = cursor.insert_sibling_before("head");
+    cursor.insert_sibling_after("footer");
+    assert_eq!(cursor.node(), body);
+    assert_eq!(cursor.left(), Some(head));
+    assert_eq!(cursor.right(), Some(body));
+    assert_eq!(cursor.down(), Some(p));
+    let div = cursor.wrap_in_parent("div");
+    assert_eq!(cursor.node(), p);
+    assert_eq!(cursor.replace("span"), "p");
+    let span = p;
+    assert_eq!(cursor.up(), Some(div));
+    assert_eq!(cursor.node(), div);
+    assert_eq!(
+        render(cursor.forest()),
+        "<html><head></head><body><div><span></span></div></body><footer></footer></html>"
+    );
+
+    assert_eq!(cursor.exit(), Some(body));
+    assert!(cursor.detach());
+    assert!(!cursor.detach());
+    assert_eq!(cursor.left(), Some(html));
+    assert_eq!(cursor.seek(SeekPos::BottomLast), Some(span));
+    assert_eq!(cursor.seek(SeekPos::TopFirst), Some(html));
+    let wrapper = cursor.wrap_in_parent("doc");
+    assert_eq!(forest.get_first_root_node(), Some(wrapper));
+    assert_eq!(
+        render(&forest),
+        "<doc><html><head></head><footer></footer></html></doc><body><div><span></span></div></body>"
+    );
+}
+
+#[test]
+fn cursor_forest_002() {
+    let mut forest: Forest<&'static str> = Forest::new();
+    let ul = forest.create_node("ul");
+    let li1 = forest.create_node("li1");
+    let li2 = forest.create_node("li2");
+    let a = forest.create_node("a");
+    forest.append_node_child(ul, li1);
+    forest.append_node_child(ul, li2);
+    forest.append_node_child(li1, a);
+
+    let cursor = forest.cursor_mut(li1);
+    let (v, cursor) = cursor.remove();
+    assert_eq!(v, "li1");
+    let cursor = cursor.unwrap();
+    assert_eq!(cursor.node(), li2);
+    let (v, cursor) = cursor.remove();
+    assert_eq!(v, "li2");
+    let cursor = cursor.unwrap();
+    assert_eq!(cursor.node(), ul);
+    let (v, cursor) = cursor.remove();
+    assert_eq!(v, "ul");
+    assert!(cursor.is_none());
+
+    assert!(forest.is_empty());
+    assert_eq!(forest.get(a), None);
+}
+
+#[test]
+#[should_panic(expected = "invalid node")]
+fn cursor_forest_003() {
+    let mut forest: Forest<usize> = Forest::new();
+    let n = forest.create_node(1usize);
+    forest.remove_node(n);
+    forest.cursor_mut(n);
+}
@@ -0,0 +1,147 @@
+extern crate forests;
+use forests::*;
+
+fn build_forest() -> (Forest<usize>, Vec<node>) {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..8usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[1], nodes[2]);
+    forest.append_node_child(nodes[2], nodes[3]);
+    forest.append_node_child(nodes[0], nodes[4]);
+    forest.append_node_child(nodes[6], nodes[7]);
+    (forest, nodes)
+}
+
+#[test]
+fn depth_forest_001() {
+    let (forest, nodes) = build_forest();
+
+    let pre_order: Vec<_> = forest.iter().with_depth().map(|(d, _, v)| (d, *v)).collect();
+    assert_eq!(
+        pre_order,
+        vec![(0, 0), (1, 1), (2, 2), (3, 3), (1, 4), (0, 5), (0, 6), (1, 7)]
+    );
+
+    let post_order: Vec<_> = forest
+        .iter()
+        .mode(IterMode::PostOrder)
+        .with_depth()
+        .map(|(d, _, v)| (d, *v))
+        .collect();
+    assert_eq!(
+        post_order,
+        vec![(3, 3), (2, 2), (1, 1), (1, 4), (0, 0), (0, 5), (1, 7), (0, 6)]
+    );
+
+    let mut iter = forest.iter().values().with_depth();
+    assert_eq!(iter.by_ref().count(), 8);
+    let mut backwards = Vec::new();
+    while let Some((d, n, v)) = iter.prev() {
+        assert_eq!(nodes[*v], n);
+        backwards.push((d, *v));
+    }
+    assert_eq!(
+        backwards,
+        vec![(1, 7), (0, 6), (0, 5), (1, 4), (3, 3), (2, 2), (1, 1), (0, 0)]
+    );
+
+    let subtree: Vec<_> = forest
+        .iter_subtree(nodes[1])
+        .with_depth()
+        .map(|(d, _, v)| (d, *v))
+        .collect();
+    assert_eq!(subtree, vec![(0, 1), (1, 2), (2, 3)]);
+}
+
+#[test]
+fn depth_forest_002() {
+    let (mut forest, nodes) = build_forest();
+
+    let mut iter = forest.iter();
+    iter.next();
+    iter.next();
+    let rest: Vec<_> = iter.with_depth().map(|(d, n, _)| (d, n)).collect();
+    assert_eq!(
+        rest,
+        vec![(2, nodes[2]), (3, nodes[3]), (1, nodes[4]), (0, nodes[5]), (0, nodes[6]), (1, nodes[7])]
+    );
+
+    for (depth, _, v) in forest.iter_mut().mode(IterMode::Both).with_depth() {
+        *v += depth * 100;
+    }
+    assert_eq!(forest[nodes[3]], 603);
+    assert_eq!(forest[nodes[0]], 0);
+    assert_eq!(forest[nodes[7]], 207);
+}
+
+#[test]
+fn depth_forest_003() {
+    let (mut forest, nodes) = build_forest();
+
+    let truncated: Vec<_> = forest.iter().max_depth(1).map(|(m, v)| (m, *v)).collect();
+    assert_eq!(
+        truncated,
+        vec![
+            (IterMovement::DownFirst(0), 0),
+            (IterMovement::DownFirst(1), 1),
+            (IterMovement::Right, 4),
+            (IterMovement::UpNRight(1), 5),
+            (IterMovement::Right, 6),
+            (IterMovement::DownFirst(1), 7),
+        ]
+    );
+
+    let truncated: Vec<_> = forest
+        .iter()
+        .mode(IterMode::PostOrder)
+        .max_depth(1)
+        .map(|(m, v)| (m, *v))
+        .collect();
+    assert_eq!(
+        truncated,
+        vec![
+            (IterMovement::DownFirst(1), 1),
+            (IterMovement::Right, 4),
+            (IterMovement::Up(1), 0),
+            (IterMovement::Right, 5),
+            (IterMovement::RightDownFirstN(1), 7),
+            (IterMovement::Up(1), 6),
+        ]
+    );
+
+    let truncated: Vec<_> = forest
+        .iter_subtree(nodes[0])
+        .max_depth(1)
+        .mode(IterMode::Both)
+        .with_depth()
+        .map(|(d, _, v)| (d, *v))
+        .collect();
+    assert_eq!(truncated, vec![(0, 0), (1, 1), (1, 1), (1, 4), (1, 4), (0, 0)]);
+
+    let mut iter = forest.iter().max_depth(0).values();
+    assert_eq!(iter.by_ref().cloned().collect::<Vec<_>>(), vec![0, 5, 6]);
+    assert_eq!(iter.prev(), Some(&6));
+    assert_eq!(iter.prev(), Some(&5));
+    assert_eq!(iter.seek(SeekPos::BottomFirst), Some(&0));
+    assert_eq!(iter.down(), None);
+
+    for v in forest.iter_subtree_mut(nodes[1]).max_depth(1).values() {
+        *v += 10;
+    }
+    assert_eq!((forest[nodes[1]], forest[nodes[2]], forest[nodes[3]]), (11, 12, 3));
+}
+
+#[test]
+fn depth_forest_004() {
+    let (forest, nodes) = build_forest();
+
+    let mut iter = forest.iter().mode(IterMode::PostOrder);
+    assert_eq!(iter.seek_node(nodes[2], false), Some(&2));
+    let mut iter = iter.with_depth();
+    assert_eq!(iter.next(), Some((1, nodes[1], &1)));
+    assert_eq!(iter.prev(), Some((2, nodes[2], &2)));
+    assert_eq!(iter.prev(), Some((3, nodes[3], &3)));
+    assert_eq!(iter.prev(), None);
+    let rest: Vec<_> = iter.map(|(d, _, v)| (d, *v)).collect();
+    assert_eq!(rest, vec![(3, 3), (2, 2), (1, 1), (1, 4), (0, 0), (0, 5), (1, 7), (0, 6)]);
+}
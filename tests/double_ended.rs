@@ -0,0 +1,181 @@
+extern crate forests;
+use forests::*;
+
+mod common;
+
+#[test]
+fn double_ended_forest_001() {
+    let (forest, nodes) = common::small_forest();
+
+    let reversed: Vec<_> = forest.iter().values().rev().cloned().collect();
+    assert_eq!(reversed, vec![5, 4, 3, 2, 1, 0]);
+    let reversed: Vec<_> = forest
+        .iter()
+        .mode(IterMode::PostOrder)
+        .values()
+        .rev()
+        .cloned()
+        .collect();
+    assert_eq!(reversed, vec![4, 5, 0, 2, 3, 1]);
+    let reversed: Vec<_> = forest.iter_subtree(nodes[0]).values().rev().cloned().collect();
+    assert_eq!(reversed, vec![3, 2, 1, 0]);
+
+    let mut iter = forest.iter().values();
+    assert_eq!(iter.next(), Some(&0));
+    assert_eq!(iter.next_back(), Some(&5));
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.next_back(), Some(&3));
+    assert_eq!(iter.next(), Some(&2));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut iter = forest.iter();
+    assert_eq!(iter.next_back(), Some((IterMovement::DownLast(1), &5)));
+    assert_eq!(iter.next_back(), Some((IterMovement::Up(1), &4)));
+    assert_eq!(iter.next_back(), Some((IterMovement::LeftDownLastN(2), &3)));
+    assert_eq!(iter.next(), Some((IterMovement::DownFirst(0), &0)));
+}
+
+#[test]
+fn double_ended_forest_002() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter().exact();
+    assert_eq!(iter.len(), 6);
+    iter.next();
+    iter.next_back();
+    assert_eq!(iter.len(), 4);
+    iter.prev();
+    assert_eq!(iter.size_hint(), (5, Some(5)));
+    assert_eq!(forest.iter().mode(IterMode::Both).exact().len(), 12);
+
+    // without a known count, the hint is only bounded by the forest length.
+    let mut iter = forest.iter_subtree(nodes[2]).values();
+    assert_eq!(iter.size_hint(), (0, Some(6)));
+    iter.next();
+    assert_eq!(iter.size_hint(), (0, Some(6)));
+    assert_eq!(forest.iter().max_depth(0).size_hint(), (0, Some(6)));
+    let mut iter = forest.iter().mode(IterMode::Both);
+    iter.seek_node(nodes[2], true);
+    assert_eq!(iter.size_hint(), (0, Some(12)));
+
+    for v in forest.iter_mut().values().rev().take(2) {
+        *v += 10;
+    }
+    assert_eq!((forest[nodes[3]], forest[nodes[4]], forest[nodes[5]]), (3, 14, 15));
+    assert_eq!(forest.iter_mut().exact().values().len(), 6);
+}
+
+#[test]
+#[should_panic(expected = "iterator length unknown")]
+fn double_ended_forest_003() {
+    let (forest, nodes) = common::small_forest();
+
+    let _ = forest.iter_subtree(nodes[0]).exact();
+}
+
+#[test]
+fn double_ended_forest_004() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let reversed: Vec<_> = forest.iter().nodes().rev().collect();
+    assert_eq!(reversed, nodes.iter().rev().cloned().collect::<Vec<_>>());
+
+    let reversed: Vec<_> = forest
+        .iter_subtree(nodes[0])
+        .with_nodes()
+        .rev()
+        .map(|(n, movement, v)| (n, movement, *v))
+        .collect();
+    assert_eq!(
+        reversed,
+        vec![
+            (nodes[3], IterMovement::DownLast(2), 3),
+            (nodes[2], IterMovement::Up(1), 2),
+            (nodes[1], IterMovement::Left, 1),
+            (nodes[0], IterMovement::Up(1), 0),
+        ]
+    );
+
+    let reversed: Vec<_> = forest.iter().with_depth().rev().map(|(d, _, v)| (d, *v)).collect();
+    assert_eq!(reversed, vec![(1, 5), (0, 4), (2, 3), (1, 2), (1, 1), (0, 0)]);
+
+    let mut events = forest.iter().events();
+    assert_eq!(events.size_hint(), (12, Some(12)));
+    assert_eq!(events.next_back(), Some((nodes[4], IterEvent::Leave(&4))));
+    assert_eq!(events.next_back(), Some((nodes[5], IterEvent::Leave(&5))));
+    assert_eq!(events.next(), Some((nodes[0], IterEvent::Enter(&0))));
+    assert_eq!(events.size_hint(), (9, Some(9)));
+
+    let mut events = forest.iter_mut().events();
+    assert_eq!(events.next_back(), Some(IterEventMut::Leave(nodes[4])));
+    assert_eq!(events.next_back(), Some(IterEventMut::Leave(nodes[5])));
+    if let Some(IterEventMut::Enter(n, v)) = events.next_back() {
+        assert_eq!(n, nodes[5]);
+        *v += 10;
+    }
+    assert_eq!(forest[nodes[5]], 15);
+}
+
+#[test]
+fn double_ended_forest_005() {
+    let (forest, nodes) = common::small_forest();
+
+    // skipping a subtree must not carry the front past the back.
+    let mut iter = forest.iter();
+    assert_eq!(iter.next().map(|(_, v)| *v), Some(0));
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(5));
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(4));
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(3));
+    iter.skip_subtree();
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    // neither may seeking, navigating or restoring.
+    let mut iter = forest.iter();
+    let start = iter.save();
+    assert_eq!(iter.next(), Some((IterMovement::DownFirst(0), &0)));
+    assert_eq!(iter.next_back(), Some((IterMovement::DownLast(1), &5)));
+    assert_eq!(iter.next_back(), Some((IterMovement::Up(1), &4)));
+    assert_eq!(iter.seek_node(nodes[4], true), None);
+    assert_eq!(iter.seek_node(nodes[5], true), None);
+    assert_eq!(iter.right(), None);
+    assert_eq!(iter.seek_node(nodes[2], true), Some(&2));
+    assert_eq!(iter.next().map(|(_, v)| *v), Some(3));
+    assert_eq!(iter.next(), None);
+    iter.restore(start);
+    assert_eq!(iter.next().map(|(_, v)| *v), Some(0));
+
+    let mut iter = forest.iter().mode(IterMode::Both);
+    let checkpoint = {
+        iter.next();
+        iter.save()
+    };
+    for _ in 0..11 {
+        iter.next_back();
+    }
+    iter.restore(checkpoint);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn double_ended_forest_006() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter_mut();
+    assert_eq!(iter.next().map(|(_, v)| *v), Some(0));
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(5));
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(4));
+    iter.skip_subtree();
+    assert_eq!(iter.next(), None);
+
+    let mut iter = forest.iter_mut();
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(5));
+    unsafe {
+        assert_eq!(iter.seek_node(nodes[5], false), None);
+        assert_eq!(iter.seek_node(nodes[4], true), Some(&mut 4));
+    }
+    assert_eq!(iter.next(), None);
+}
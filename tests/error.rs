@@ -0,0 +1,42 @@
+extern crate forests;
+use forests::*;
+
+#[test]
+fn error_forest_001() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    let node3 = forest.create_node(3usize);
+    let node4 = forest.create_node(4usize);
+
+    assert_eq!(forest.try_append_node_child(node1, node2), Ok(()));
+    assert_eq!(forest.try_append_node_child(node2, node3), Ok(()));
+    assert_eq!(
+        forest.try_append_node_child(node3, node1),
+        Err(ForestError::WouldCreateCycle)
+    );
+    assert_eq!(
+        forest.try_prepend_node_child(node1, node1),
+        Err(ForestError::SameNode)
+    );
+    assert_eq!(
+        forest.try_insert_node_child_before(node1, node4, node3),
+        Err(ForestError::ReferentNotChild)
+    );
+    assert_eq!(
+        forest.try_insert_node_child_after(node1, node2, node2),
+        Err(ForestError::SameNode)
+    );
+    assert_eq!(forest.try_insert_node_child_after(node1, node4, node2), Ok(()));
+
+    forest.remove_node(node4);
+    assert_eq!(
+        forest.try_append_node_child(node1, node4),
+        Err(ForestError::InvalidNode)
+    );
+    assert_eq!(forest.try_get_parent_node(node4), Err(ForestError::InvalidNode));
+    assert_eq!(forest.try_get_parent_node(node1), Ok(None));
+    assert_eq!(forest.try_get_first_child_node(node1), Ok(Some(node2)));
+    assert_eq!(forest.try_get_last_child_node(node1), Ok(Some(node2)));
+    assert_eq!(forest.try_get_next_sibling_node(node2), Ok(None));
+}
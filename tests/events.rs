@@ -0,0 +1,68 @@
+extern crate forests;
+use forests::*;
+
+#[test]
+fn events_forest_001() {
+    let mut forest: Forest<&'static str> = Forest::new();
+    let html = forest.create_node("html");
+    let head = forest.create_node("head");
+    let body = forest.create_node("body");
+    let p = forest.create_node("p");
+    forest.append_node_child(html, head);
+    forest.append_node_child(html, body);
+    forest.append_node_child(body, p);
+
+    let mut output = String::new();
+    for (_, event) in forest.iter().events() {
+        match event {
+            IterEvent::Enter(tag) => output += &format!("<{}>", tag),
+            IterEvent::Leave(tag) => output += &format!("</{}>", tag),
+        }
+    }
+    assert_eq!(output, "<html><head></head><body><p></p></body></html>");
+
+    let expected_output = vec![
+        (html, IterEvent::Enter(&"html")),
+        (head, IterEvent::Enter(&"head")),
+        (head, IterEvent::Leave(&"head")),
+        (body, IterEvent::Enter(&"body")),
+        (p, IterEvent::Enter(&"p")),
+        (p, IterEvent::Leave(&"p")),
+        (body, IterEvent::Leave(&"body")),
+        (html, IterEvent::Leave(&"html")),
+    ];
+    let actual_output: Vec<_> = forest.iter().events().collect();
+    assert_eq!(expected_output, actual_output);
+
+    let mut events = forest.iter_subtree(body).events();
+    assert_eq!(events.by_ref().count(), 4);
+    assert_eq!(events.prev(), Some((body, IterEvent::Leave(&"body"))));
+    assert_eq!(events.prev(), Some((p, IterEvent::Leave(&"p"))));
+    assert_eq!(events.prev(), Some((p, IterEvent::Enter(&"p"))));
+}
+
+#[test]
+fn events_forest_002() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    forest.append_node_child(node1, node2);
+
+    for event in forest.iter_mut().events() {
+        match event {
+            IterEventMut::Enter(_, v) => *v *= 10,
+            IterEventMut::Leave(n) => assert!(n == node1 || n == node2),
+        }
+    }
+    assert_eq!((forest[node1], forest[node2]), (10usize, 20usize));
+
+    let visits: Vec<_> = forest
+        .iter()
+        .events()
+        .map(|(n, event)| (n, event.is_enter()))
+        .collect();
+    assert_eq!(
+        visits,
+        vec![(node1, true), (node2, true), (node2, false), (node1, false)]
+    );
+}
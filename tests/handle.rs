@@ -0,0 +1,45 @@
+extern crate forests;
+use forests::*;
+
+#[test]
+fn handle_forest_001() {
+    let mut forest1: Forest<usize> = Forest::with_identity();
+    let mut forest2: Forest<usize> = Forest::with_identity();
+    let node1 = forest1.create_node(1usize);
+    let node2 = forest1.create_node(2usize);
+    let node3 = forest2.create_node(3usize);
+    let node4 = forest2.create_node(4usize);
+
+    assert_eq!(forest1.get_next_sibling_node(node1), Some(node2));
+    assert_eq!(forest2.get_next_sibling_node(node1), None);
+    assert!(!forest2.append_node_child(node3, node2));
+    assert!(!forest1.append_node_child(node1, node4));
+    assert_eq!(forest2.remove_node(node1), None);
+    assert!(forest1.append_node_child(node1, node2));
+
+    // handles from an anonymous forest are rejected as well.
+    let mut forest3: Forest<usize> = Forest::new();
+    let node5 = forest3.create_node(5usize);
+    assert_eq!(forest1.get_parent_node(node5), None);
+    assert_eq!(forest3.get_parent_node(node5), None);
+    assert_eq!(forest3.get_first_root_node(), Some(node5));
+}
+
+#[test]
+fn handle_forest_002() {
+    let mut forest1: Forest<usize> = Forest::new();
+    let mut forest2: Forest<usize> = Forest::new();
+    let node1 = forest1.create_node(1usize);
+    let _ = forest2.create_node(2usize);
+    let node3 = forest2.create_node(3usize);
+
+    // a handle past the end of an anonymous forest is just invalid there.
+    assert_eq!(forest1.get(node3), None);
+    assert_eq!(forest1.get_parent_node(node3), None);
+    assert_eq!(forest1.try_get_parent_node(node3), Err(ForestError::InvalidNode));
+    assert!(!forest1.detach_node(node3));
+    assert!(!forest1.append_node_child(node1, node3));
+    assert_eq!(forest1.remove_node(node3), None);
+    assert!(!forest1.remove_subtree(node3));
+    assert_eq!(forest1.len(), 1);
+}
@@ -0,0 +1,54 @@
+extern crate forests;
+use forests::*;
+
+#[test]
+fn nodes_forest_001() {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..6usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[0], nodes[2]);
+    forest.append_node_child(nodes[2], nodes[3]);
+    forest.append_node_child(nodes[4], nodes[5]);
+
+    let visited: Vec<_> = forest.iter().nodes().collect();
+    assert_eq!(
+        visited,
+        vec![nodes[0], nodes[1], nodes[2], nodes[3], nodes[4], nodes[5]]
+    );
+    let visited: Vec<_> = forest.iter().mode(IterMode::PostOrder).nodes().collect();
+    assert_eq!(
+        visited,
+        vec![nodes[1], nodes[3], nodes[2], nodes[0], nodes[5], nodes[4]]
+    );
+
+    let mut with_nodes = forest.iter_subtree(nodes[2]).with_nodes();
+    assert_eq!(
+        with_nodes.next(),
+        Some((nodes[2], IterMovement::DownFirst(0), &2))
+    );
+    assert_eq!(
+        with_nodes.next(),
+        Some((nodes[3], IterMovement::DownFirst(1), &3))
+    );
+    assert_eq!(with_nodes.next(), None);
+    assert_eq!(
+        with_nodes.prev(),
+        Some((nodes[3], IterMovement::DownLast(1), &3))
+    );
+
+    let odd: Vec<_> = forest
+        .iter()
+        .with_nodes()
+        .filter(|&(_, _, v)| v % 2 == 1)
+        .map(|(n, _, _)| n)
+        .collect();
+    for n in odd {
+        forest.remove_subtree(n);
+    }
+    assert_eq!(forest.iter().values().cloned().collect::<Vec<_>>(), vec![0, 2, 4]);
+
+    for (n, _, v) in forest.iter_mut().with_nodes() {
+        *v += if n == nodes[2] { 10 } else { 0 };
+    }
+    assert_eq!(forest[nodes[2]], 12);
+}
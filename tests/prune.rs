@@ -0,0 +1,85 @@
+extern crate forests;
+use forests::*;
+
+fn build_forest() -> (Forest<usize>, Vec<node>) {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..8usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[1], nodes[2]);
+    forest.append_node_child(nodes[0], nodes[3]);
+    forest.append_node_child(nodes[3], nodes[4]);
+    forest.append_node_child(nodes[5], nodes[6]);
+    forest.append_node_child(nodes[6], nodes[7]);
+    (forest, nodes)
+}
+
+#[test]
+fn prune_forest_001() {
+    let (forest, _) = build_forest();
+
+    let mut iter = forest.iter();
+    let mut output = Vec::new();
+    while let Some((movement, v)) = iter.next() {
+        output.push((movement, *v));
+        if *v == 1 || *v == 5 {
+            iter.skip_subtree();
+        }
+    }
+    assert_eq!(
+        output,
+        vec![
+            (IterMovement::DownFirst(0), 0),
+            (IterMovement::DownFirst(1), 1),
+            (IterMovement::Right, 3),
+            (IterMovement::DownFirst(1), 4),
+            (IterMovement::UpNRight(2), 5),
+        ]
+    );
+
+    let pruned: Vec<_> = forest.iter().prune_where(|v| *v == 3).values().cloned().collect();
+    assert_eq!(pruned, vec![0, 1, 2, 3, 5, 6, 7]);
+
+    let pruned: Vec<_> = forest
+        .iter_subtree(forest.get_first_root_node().unwrap())
+        .prune_where(|v| *v == 0)
+        .values()
+        .cloned()
+        .collect();
+    assert_eq!(pruned, vec![0]);
+}
+
+#[test]
+fn prune_forest_002() {
+    let (mut forest, nodes) = build_forest();
+
+    let events: Vec<_> = forest
+        .iter()
+        .mode(IterMode::Both)
+        .prune_where(|v| *v == 1 || *v == 6)
+        .map(|(movement, v)| (movement, *v))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            (IterMovement::DownFirst(0), 0),
+            (IterMovement::DownFirst(1), 1),
+            (IterMovement::None, 1),
+            (IterMovement::Right, 3),
+            (IterMovement::DownFirst(1), 4),
+            (IterMovement::None, 4),
+            (IterMovement::Up(1), 3),
+            (IterMovement::Up(1), 0),
+            (IterMovement::Right, 5),
+            (IterMovement::DownFirst(1), 6),
+            (IterMovement::None, 6),
+            (IterMovement::Up(1), 5),
+        ]
+    );
+
+    for (_, v) in forest.iter_mut().prune_where(|v| *v == 3) {
+        *v += 10;
+    }
+    assert_eq!(forest[nodes[3]], 13);
+    assert_eq!(forest[nodes[4]], 4);
+    assert_eq!(forest[nodes[7]], 17);
+}
@@ -0,0 +1,92 @@
+extern crate forests;
+use forests::*;
+
+#[test]
+fn remove_forest_001() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    let node3 = forest.create_node(3usize);
+    let node4 = forest.create_node(4usize);
+    forest.append_node_child(node2, node3);
+    forest.append_node_child(node2, node4);
+
+    assert_eq!(forest.remove_node(node2), Some(2usize));
+    assert_eq!(forest.len(), 3);
+
+    let expected_output: Vec<(_, _)> = vec![
+        (IterMovement::DownFirst(0), &1usize),
+        (IterMovement::Right, &3usize),
+        (IterMovement::Right, &4usize),
+    ];
+    let actual_output: Vec<(_, _)> = forest.iter().collect::<Vec<_>>();
+    assert_eq!(expected_output.as_slice(), actual_output.as_slice());
+
+    assert_eq!(forest.remove_node(node1), Some(1usize));
+    assert_eq!(forest.get_first_root_node(), Some(node3));
+}
+
+#[test]
+fn remove_forest_002() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    let node3 = forest.create_node(3usize);
+    let node4 = forest.create_node(4usize);
+    forest.append_node_child(node1, node2);
+    forest.append_node_child(node2, node3);
+    forest.append_node_child(node1, node4);
+
+    assert!(forest.remove_subtree(node2));
+    assert_eq!(forest.len(), 2);
+
+    let node5 = forest.create_node(5usize);
+    let node6 = forest.create_node(6usize);
+    let _node7 = forest.create_node(7usize);
+    forest.append_node_child(node4, node5);
+    forest.prepend_node_child(node1, node6);
+
+    let expected_output: Vec<(_, _)> = vec![
+        (IterMovement::DownFirst(0), &1usize),
+        (IterMovement::DownFirst(1), &6usize),
+        (IterMovement::Right, &4usize),
+        (IterMovement::DownFirst(1), &5usize),
+        (IterMovement::UpNRight(2), &7usize),
+    ];
+    let actual_output: Vec<(_, _)> = forest.iter().collect::<Vec<_>>();
+    assert_eq!(expected_output.as_slice(), actual_output.as_slice());
+    assert_eq!(forest.len(), 5);
+}
+
+#[test]
+fn remove_forest_003() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    forest.remove_node(node1);
+    assert!(forest.is_empty());
+    assert_eq!(forest.get_first_root_node(), None);
+    assert_eq!(forest.iter().count(), 0);
+}
+
+#[test]
+fn remove_forest_004() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    forest.append_node_child(node1, node2);
+    forest.remove_node(node2);
+
+    // the slot of node2 is reused, but the old handle stays stale.
+    let node3 = forest.create_node(3usize);
+    assert!(node2 != node3);
+    assert_eq!(forest.get_parent_node(node2), None);
+    assert_eq!(forest.get_next_sibling_node(node2), None);
+    assert_eq!(forest.get_next_sibling_node(node1), Some(node3));
+    assert!(!forest.append_node_child(node1, node2));
+    assert!(!forest.append_node_child(node2, node3));
+    assert!(!forest.detach_node(node2));
+    assert!(!forest.remove_subtree(node2));
+    assert_eq!(forest.remove_node(node2), None);
+    assert_eq!(forest.remove_node(node3), Some(3usize));
+    assert_eq!(forest.len(), 1);
+}
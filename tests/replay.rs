@@ -0,0 +1,79 @@
+extern crate forests;
+use forests::*;
+
+fn build_forest() -> Forest<usize> {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..10usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[1], nodes[2]);
+    forest.append_node_child(nodes[2], nodes[3]);
+    forest.append_node_child(nodes[1], nodes[4]);
+    forest.append_node_child(nodes[0], nodes[5]);
+    forest.append_node_child(nodes[7], nodes[8]);
+    forest.append_node_child(nodes[7], nodes[9]);
+    forest
+}
+
+type Movements = Vec<(IterMovement, usize)>;
+
+fn both_orders(forest: &Forest<usize>) -> (Movements, Movements) {
+    let pre_order = forest.iter().map(|(m, v)| (m, *v)).collect();
+    let post_order = forest
+        .iter()
+        .mode(IterMode::PostOrder)
+        .map(|(m, v)| (m, *v))
+        .collect();
+    (pre_order, post_order)
+}
+
+#[test]
+fn replay_forest_001() {
+    let forest = build_forest();
+    let expected_output = both_orders(&forest);
+
+    let rebuilt: Forest<usize> = forest.iter().map(|(m, v)| (m, *v)).collect();
+    assert_eq!(both_orders(&rebuilt), expected_output);
+    assert_eq!(rebuilt.len(), forest.len());
+
+    let rebuilt: Forest<usize> = forest
+        .iter()
+        .mode(IterMode::PostOrder)
+        .map(|(m, v)| (m, *v))
+        .collect();
+    assert_eq!(both_orders(&rebuilt), expected_output);
+
+    let flat: Forest<usize> = Forest::from_movements(vec![
+        (IterMovement::DownFirst(0), 1usize),
+        (IterMovement::Right, 2usize),
+    ]).unwrap();
+    assert_eq!(flat.iter().values().cloned().collect::<Vec<_>>(), vec![1, 2]);
+
+    let empty: Forest<usize> = Forest::from_movements(vec![]).unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn replay_forest_002() {
+    let malformed_streams = vec![
+        vec![(IterMovement::Right, 1usize)],
+        vec![(IterMovement::DownFirst(0), 1usize), (IterMovement::Up(1), 2usize)],
+        vec![(IterMovement::DownFirst(2), 1usize), (IterMovement::Up(1), 2usize)],
+        vec![
+            (IterMovement::DownFirst(0), 1usize),
+            (IterMovement::DownFirst(1), 2usize),
+            (IterMovement::UpNRight(2), 3usize),
+        ],
+        vec![
+            (IterMovement::DownFirst(0), 1usize),
+            (IterMovement::DownFirst(1), 2usize),
+            (IterMovement::RightDownFirstN(1), 3usize),
+        ],
+        vec![(IterMovement::DownFirst(0), 1usize), (IterMovement::Left, 2usize)],
+    ];
+    for stream in malformed_streams {
+        assert_eq!(
+            Forest::from_movements(stream).err(),
+            Some(ForestError::InvalidMovement)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+extern crate forests;
+use forests::*;
+
+fn collect_roots(forest: &Forest<usize>) -> Vec<usize> {
+    let mut output = Vec::new();
+    let mut cur = forest.get_first_root_node();
+    while let Some(n) = cur {
+        output.push(forest[n]);
+        cur = forest.get_next_sibling_node(n);
+    }
+    output
+}
+
+#[test]
+fn roots_forest_001() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    let node3 = forest.create_node(3usize);
+    let node4 = forest.create_node(4usize);
+
+    assert!(forest.insert_root_before(node4, node2));
+    assert_eq!(collect_roots(&forest), vec![1, 4, 2, 3]);
+    assert!(forest.insert_root_after(node1, node3));
+    assert_eq!(collect_roots(&forest), vec![4, 2, 3, 1]);
+    assert!(forest.prepend_root(node3));
+    assert_eq!(collect_roots(&forest), vec![3, 4, 2, 1]);
+    assert!(forest.prepend_root(node3));
+    assert_eq!(collect_roots(&forest), vec![3, 4, 2, 1]);
+    assert!(forest.move_to_root_at(node3, 2));
+    assert_eq!(collect_roots(&forest), vec![4, 2, 3, 1]);
+    assert!(forest.move_to_root_at(node4, 100));
+    assert_eq!(collect_roots(&forest), vec![2, 3, 1, 4]);
+}
+
+#[test]
+fn roots_forest_002() {
+    let mut forest: Forest<usize> = Forest::new();
+    let node1 = forest.create_node(1usize);
+    let node2 = forest.create_node(2usize);
+    let node3 = forest.create_node(3usize);
+    forest.append_node_child(node1, node2);
+    forest.append_node_child(node2, node3);
+
+    assert_eq!(
+        forest.try_insert_root_before(node1, node2),
+        Err(ForestError::ReferentNotChild)
+    );
+    assert_eq!(
+        forest.try_insert_root_after(node1, node1),
+        Err(ForestError::SameNode)
+    );
+    assert!(forest.insert_root_before(node2, node1));
+    assert_eq!(collect_roots(&forest), vec![2, 1]);
+    assert_eq!(forest.get_first_child_node(node1), None);
+    assert!(forest.move_to_root_at(node3, 1));
+    assert_eq!(collect_roots(&forest), vec![2, 3, 1]);
+    assert_eq!(forest.get_first_child_node(node2), None);
+}
+
+#[test]
+fn roots_forest_003() {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..1000usize).map(|v| forest.create_node(v)).collect();
+    assert_eq!(forest.get_first_root_node(), Some(nodes[0]));
+    assert_eq!(forest.get_last_root_node(), Some(nodes[999]));
+
+    forest.append_node_child(nodes[998], nodes[999]);
+    assert_eq!(forest.get_last_root_node(), Some(nodes[998]));
+    forest.append_node_child(nodes[1], nodes[0]);
+    assert_eq!(forest.get_first_root_node(), Some(nodes[1]));
+    forest.remove_subtree(nodes[998]);
+    assert_eq!(forest.get_last_root_node(), Some(nodes[997]));
+    forest.remove_node(nodes[1]);
+    assert_eq!(forest.get_first_root_node(), Some(nodes[2]));
+    assert_eq!(forest.get_last_root_node(), Some(nodes[0]));
+    assert_eq!(forest.len(), 997);
+
+    let mut forest: Forest<usize> = Forest::new();
+    assert_eq!(forest.get_last_root_node(), None);
+    let node1 = forest.create_node(1usize);
+    forest.remove_node(node1);
+    assert_eq!(forest.get_first_root_node(), None);
+    assert_eq!(forest.get_last_root_node(), None);
+}
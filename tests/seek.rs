@@ -0,0 +1,98 @@
+extern crate forests;
+use forests::*;
+
+mod common;
+
+#[test]
+fn seek_forest_001() {
+    let (forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter();
+    assert_eq!(iter.seek_node(nodes[2], true), Some(&2));
+    assert_eq!(iter.next(), Some((IterMovement::DownFirst(1), &3)));
+    assert_eq!(iter.next(), Some((IterMovement::UpNRight(2), &4)));
+
+    assert_eq!(iter.seek_node(nodes[2], true), Some(&2));
+    assert_eq!(iter.prev(), Some((IterMovement::Left, &1)));
+    assert_eq!(iter.prev(), Some((IterMovement::Up(1), &0)));
+
+    let mut iter = forest.iter().mode(IterMode::Both);
+    assert_eq!(iter.seek_node(nodes[2], false), Some(&2));
+    assert_eq!(iter.next(), Some((IterMovement::Up(1), &0)));
+    assert_eq!(iter.last_visit(), Some(IterEvent::Leave(nodes[0])));
+
+    let mut iter = forest.iter_subtree(nodes[2]);
+    assert_eq!(iter.seek_node(nodes[1], true), None);
+    assert_eq!(iter.seek_node(nodes[3], true), Some(&3));
+    assert_eq!(iter.next(), None);
+
+    let mut iter = forest.iter().max_depth(0);
+    assert_eq!(iter.seek_node(nodes[3], true), None);
+    assert_eq!(iter.seek_node(nodes[0], true), Some(&0));
+    assert_eq!(iter.next(), Some((IterMovement::Right, &4)));
+
+    let mut other: Forest<usize> = Forest::with_identity();
+    let foreign = other.create_node(0);
+    let mut iter = forest.iter();
+    assert_eq!(iter.seek_node(foreign, true), None);
+}
+
+#[test]
+fn seek_forest_002() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter();
+    let mut matches = Vec::new();
+    while let Some((_, v)) = iter.next() {
+        if *v % 2 == 0 {
+            let checkpoint = iter.save();
+            matches.push((*v, iter.next().map(|(_, v)| *v)));
+            iter.restore(checkpoint);
+        }
+    }
+    assert_eq!(
+        matches,
+        vec![(0, Some(1)), (2, Some(3)), (4, Some(5))]
+    );
+
+    let mut iter = forest.iter_mut();
+    iter.next();
+    let checkpoint = iter.save();
+    for (_, v) in iter.by_ref() {
+        *v += 10;
+    }
+    // the values yielded by the loop above are gone.
+    unsafe { iter.restore(checkpoint) };
+    let (_, v) = iter.next().unwrap();
+    *v += 100;
+    assert_eq!(forest[nodes[0]], 0);
+    assert_eq!(forest[nodes[1]], 111);
+    assert_eq!(forest[nodes[5]], 15);
+}
+
+#[test]
+#[should_panic(expected = "invalid checkpoint")]
+fn seek_forest_003() {
+    let (forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter();
+    iter.seek_node(nodes[1], true);
+    let checkpoint = iter.save();
+    forest.iter_subtree(nodes[2]).restore(checkpoint);
+}
+
+#[test]
+fn seek_forest_004() {
+    let (mut forest, nodes) = common::small_forest();
+
+    let mut iter = forest.iter_mut();
+    unsafe {
+        *iter.seek_node(nodes[2], true).unwrap() += 10;
+        *iter.seek_node(nodes[2], true).unwrap() += 10;
+    }
+    let (_, v) = iter.next().unwrap();
+    *v += 10;
+    assert_eq!(unsafe { iter.seek_node(nodes[4], false) }.map(|v| *v), Some(4));
+    assert_eq!(forest[nodes[2]], 22);
+    assert_eq!(forest[nodes[3]], 13);
+}
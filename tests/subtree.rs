@@ -0,0 +1,97 @@
+extern crate forests;
+use forests::*;
+
+fn build_forest() -> (Forest<usize>, Vec<node>) {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..7usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[0], nodes[2]);
+    forest.append_node_child(nodes[2], nodes[3]);
+    forest.append_node_child(nodes[2], nodes[4]);
+    forest.append_node_child(nodes[5], nodes[6]);
+    (forest, nodes)
+}
+
+#[test]
+fn subtree_forest_001() {
+    let (forest, nodes) = build_forest();
+
+    let expected_output: Vec<(_, _)> = vec![
+        (IterMovement::DownFirst(0), &2usize),
+        (IterMovement::DownFirst(1), &3usize),
+        (IterMovement::Right, &4usize),
+    ];
+    let actual_output: Vec<(_, _)> = forest.iter_subtree(nodes[2]).collect::<Vec<_>>();
+    assert_eq!(expected_output.as_slice(), actual_output.as_slice());
+
+    let expected_output: Vec<(_, _)> = vec![
+        (IterMovement::DownFirst(1), &3usize),
+        (IterMovement::Right, &4usize),
+        (IterMovement::Up(1), &2usize),
+    ];
+    let actual_output: Vec<(_, _)> = forest
+        .iter_subtree(nodes[2])
+        .mode(IterMode::PostOrder)
+        .collect::<Vec<_>>();
+    assert_eq!(expected_output.as_slice(), actual_output.as_slice());
+
+    let expected_output: Vec<(_, _)> = vec![
+        (IterMovement::DownFirst(0), &2usize),
+        (IterMovement::DownFirst(1), &3usize),
+        (IterMovement::None, &3usize),
+        (IterMovement::Right, &4usize),
+        (IterMovement::None, &4usize),
+        (IterMovement::Up(1), &2usize),
+    ];
+    let actual_output: Vec<(_, _)> = forest
+        .iter_subtree(nodes[2])
+        .mode(IterMode::Both)
+        .collect::<Vec<_>>();
+    assert_eq!(expected_output.as_slice(), actual_output.as_slice());
+
+    let actual_output: Vec<_> = forest.iter_subtree(nodes[6]).values().collect();
+    assert_eq!(actual_output, vec![&6usize]);
+}
+
+#[test]
+fn subtree_forest_002() {
+    let (forest, nodes) = build_forest();
+
+    let mut iter = forest.iter_subtree(nodes[2]);
+    assert_eq!(iter.by_ref().count(), 3);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.prev(), Some((IterMovement::DownLast(1), &4usize)));
+    assert_eq!(iter.prev(), Some((IterMovement::Left, &3usize)));
+    assert_eq!(iter.prev(), Some((IterMovement::Up(1), &2usize)));
+    assert_eq!(iter.prev(), None);
+    assert_eq!(iter.prev(), None);
+    assert_eq!(iter.next(), Some((IterMovement::DownFirst(0), &2usize)));
+
+    let mut iter = forest.iter().values();
+    assert_eq!(iter.by_ref().count(), 7);
+    let mut actual_output = Vec::new();
+    while let Some(v) = iter.prev() {
+        actual_output.push(*v);
+    }
+    assert_eq!(actual_output, vec![6usize, 5, 4, 3, 2, 1, 0]);
+}
+
+#[test]
+fn subtree_forest_003() {
+    let (mut forest, nodes) = build_forest();
+
+    {
+        let mut iter = forest.iter_subtree_mut(nodes[2]).values();
+        assert_eq!(iter.seek(SeekPos::TopLast).map(|v| *v), Some(2usize));
+        assert!(iter.up().is_none());
+        assert!(iter.right().is_none());
+        assert_eq!(iter.seek(SeekPos::BottomLast).map(|v| *v), Some(4usize));
+        assert_eq!(iter.up().map(|v| *v), Some(2usize));
+    }
+
+    for v in forest.iter_subtree_mut(nodes[2]).values() {
+        *v += 10;
+    }
+    let actual_output: Vec<_> = forest.iter().values().cloned().collect();
+    assert_eq!(actual_output, vec![0usize, 1, 12, 13, 14, 5, 6]);
+}
@@ -0,0 +1,194 @@
+extern crate forests;
+use forests::*;
+
+fn build_forest() -> (Forest<usize>, Vec<node>) {
+    let mut forest: Forest<usize> = Forest::new();
+    let nodes: Vec<_> = (0..5usize).map(|v| forest.create_node(v)).collect();
+    forest.append_node_child(nodes[0], nodes[1]);
+    forest.append_node_child(nodes[1], nodes[2]);
+    forest.append_node_child(nodes[0], nodes[3]);
+    (forest, nodes)
+}
+
+fn movements(forest: &forest<usize>, n: node) -> Vec<(IterMovement, usize)> {
+    forest.iter_subtree(n).map(|(m, v)| (m, *v)).collect()
+}
+
+#[test]
+fn transfer_forest_001() {
+    let (mut forest, nodes) = build_forest();
+
+    let copy = forest.clone_subtree(nodes[0]);
+    assert_eq!(forest.len(), 9);
+    assert_eq!(forest.get_last_root_node(), Some(copy));
+    assert_eq!(movements(&forest, copy), movements(&forest, nodes[0]));
+    forest[copy] = 10;
+    assert_eq!(forest[nodes[0]], 0);
+
+    let leaf_copy = forest.clone_subtree(nodes[2]);
+    assert_eq!(forest.get_parent_node(leaf_copy), None);
+    assert_eq!(forest.get_first_child_node(leaf_copy), None);
+    assert_eq!(forest[leaf_copy], 2);
+}
+
+#[test]
+fn transfer_forest_002() {
+    let (template, nodes) = build_forest();
+    let mut forest: Forest<usize> = Forest::with_identity();
+    let existing = forest.create_node(100);
+
+    let (first, mapping) = forest.import_subtree(&template, nodes[0]);
+    let (second, _) = forest.import_subtree(&template, nodes[0]);
+    assert_ne!(first, second);
+    assert_eq!(mapping.len(), 4);
+    assert_eq!(mapping[&nodes[0]], first);
+    assert!(!mapping.contains_key(&nodes[4]));
+    for (old, new) in &mapping {
+        assert_eq!(template[*old], forest[*new]);
+    }
+    assert_eq!(movements(&forest, first), movements(&template, nodes[0]));
+    assert_eq!(
+        forest.iter().mode(IterMode::PostOrder).values().cloned().collect::<Vec<_>>(),
+        vec![100, 2, 1, 3, 0, 2, 1, 3, 0]
+    );
+    assert_eq!(forest.get_first_root_node(), Some(existing));
+    assert_eq!(forest.get(nodes[1]), None);
+}
+
+#[test]
+fn transfer_forest_003() {
+    let mut source: Forest<String> = Forest::new();
+    let root = source.create_node("root".to_string());
+    let a = source.create_node("a".to_string());
+    let b = source.create_node("b".to_string());
+    let c = source.create_node("c".to_string());
+    let other_root = source.create_node("other".to_string());
+    source.append_node_child(root, a);
+    source.append_node_child(a, b);
+    source.append_node_child(root, c);
+
+    let mut target: Forest<String> = Forest::with_identity();
+    let existing = target.create_node("existing".to_string());
+    let (moved, mapping) = target.transplant_from(&mut source, a).unwrap();
+
+    assert_eq!(source.len(), 3);
+    assert_eq!(source.get(a), None);
+    assert_eq!(source.get(b), None);
+    assert_eq!(source.get_first_child_node(root), Some(c));
+    assert_eq!(source.get_next_sibling_node(root), Some(other_root));
+
+    assert_eq!(mapping.len(), 2);
+    assert_eq!(mapping[&a], moved);
+    assert_eq!(target.get_next_sibling_node(existing), Some(moved));
+    assert_eq!(target.get_first_child_node(moved), Some(mapping[&b]));
+    assert_eq!(target[mapping[&b]], "b");
+    assert_eq!(target.len(), 3);
+
+    let reused = source.create_node("reused".to_string());
+    assert_ne!(reused, a);
+    assert_ne!(reused, b);
+    assert_eq!(target.transplant_from(&mut source, a), Err(ForestError::InvalidNode));
+}
+
+fn fragment(values: &[usize]) -> Forest<usize> {
+    let mut fragment: Forest<usize> = Forest::with_identity();
+    let removed = fragment.create_node(99);
+    for &v in values {
+        let root = fragment.create_node(v);
+        let child = fragment.create_node(v * 10);
+        fragment.append_node_child(root, child);
+    }
+    fragment.remove_node(removed);
+    fragment
+}
+
+#[test]
+fn transfer_forest_004() {
+    let mut forest: Forest<usize> = Forest::new();
+    let parent = forest.create_node(0);
+    let child = forest.create_node(5);
+    let top = forest.create_node(7);
+    forest.append_node_child(parent, child);
+
+    let appended = forest.append_forest(parent, fragment(&[1, 2])).unwrap();
+    assert_eq!(appended.len(), 2);
+    assert_eq!(forest.get_parent_node(appended[0]), Some(parent));
+    assert_eq!(forest[appended[1]], 2);
+    let prepended = forest.prepend_forest(parent, fragment(&[3])).unwrap();
+    let before = forest.insert_forest_before(child, fragment(&[4])).unwrap();
+    let after = forest.insert_forest_after(top, fragment(&[6])).unwrap();
+    assert_eq!(forest.insert_forest_after(top, Forest::new()), Ok(vec![]));
+
+    assert_eq!(forest.len(), 3 + 5 * 2);
+    assert_eq!(
+        forest.children(parent).map(|n| forest[n]).collect::<Vec<_>>(),
+        vec![3, 4, 5, 1, 2]
+    );
+    assert_eq!(forest.get_first_child_node(parent), Some(prepended[0]));
+    assert_eq!(forest.get_next_sibling_node(before[0]), Some(child));
+    assert_eq!(forest.get_last_root_node(), Some(after[0]));
+    assert_eq!(
+        forest.iter().values().cloned().collect::<Vec<_>>(),
+        vec![0, 3, 30, 4, 40, 5, 1, 10, 2, 20, 7, 6, 60]
+    );
+    assert_eq!(
+        forest.iter().values().rev().cloned().collect::<Vec<_>>(),
+        vec![60, 6, 7, 20, 2, 10, 1, 5, 40, 4, 30, 3, 0]
+    );
+
+    let grafted_child = forest.get_first_child_node(appended[0]).unwrap();
+    assert_eq!(forest.get_parent_node(grafted_child), Some(appended[0]));
+    assert!(forest.remove_subtree(appended[0]));
+    assert_eq!(forest.get(grafted_child), None);
+    assert_eq!(forest.len(), 11);
+    let stale = forest.append_forest(grafted_child, fragment(&[8]));
+    assert_eq!(stale, Err(ForestError::InvalidNode));
+    let stale = forest.insert_forest_before(grafted_child, fragment(&[8]));
+    assert_eq!(stale, Err(ForestError::InvalidNode));
+    assert_eq!(forest.len(), 11);
+}
+
+#[test]
+fn transfer_forest_005() {
+    let (mut forest, nodes) = build_forest();
+
+    let (tree, mapping) = forest.split_off(nodes[1]).unwrap();
+    assert_eq!(tree.len(), 2);
+    assert_eq!(mapping.len(), 2);
+    assert_eq!(tree[mapping[&nodes[1]]], 1);
+    assert_eq!(tree.get_first_child_node(mapping[&nodes[1]]), Some(mapping[&nodes[2]]));
+    assert_eq!(tree.get_first_root_node(), Some(mapping[&nodes[1]]));
+    assert_eq!(forest.len(), 3);
+    assert_eq!(forest.get(nodes[2]), None);
+    assert_eq!(
+        forest.children(nodes[0]).map(|n| forest[n]).collect::<Vec<_>>(),
+        vec![3]
+    );
+    assert_eq!(forest.split_off(nodes[2]).err(), Some(ForestError::InvalidNode));
+
+    let trees = forest.into_trees();
+    assert_eq!(trees.len(), 2);
+    let (ref first, ref first_mapping) = trees[0];
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[first_mapping[&nodes[3]]], 3);
+    assert_eq!(first.iter().values().cloned().collect::<Vec<_>>(), vec![0, 3]);
+    let (ref second, ref second_mapping) = trees[1];
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[second_mapping[&nodes[4]]], 4);
+}
+
+#[test]
+fn transfer_forest_006() {
+    let mut forest: Forest<usize> = Forest::with_identity();
+    let root = forest.create_node(1);
+    let child = forest.create_node(2);
+    forest.append_node_child(root, child);
+
+    let (tree, mapping) = forest.split_off(root).unwrap();
+    assert!(forest.is_empty());
+    assert_eq!(tree.get(root), None);
+    assert_eq!(tree.get(mapping[&child]), Some(&2));
+
+    let trees = forest.into_trees();
+    assert!(trees.is_empty());
+}
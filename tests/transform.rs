@@ -0,0 +1,116 @@
+extern crate forests;
+use forests::*;
+use std::ops::Bound;
+
+fn render(forest: &Forest<&'static str>) -> String {
+    let mut output = String::new();
+    for (_, event) in forest.iter().events() {
+        match event {
+            IterEvent::Enter(tag) => output += &format!("<{}>", tag),
+            IterEvent::Leave(tag) => output += &format!("</{}>", tag),
+        }
+    }
+    output
+}
+
+fn build_list() -> (Forest<&'static str>, node, Vec<node>) {
+    let mut forest: Forest<&'static str> = Forest::new();
+    let ul = forest.create_node("ul");
+    let items: Vec<_> = ["a", "b", "c", "d"]
+        .iter()
+        .map(|&tag| {
+            let item = forest.create_node(tag);
+            forest.append_node_child(ul, item);
+            item
+        })
+        .collect();
+    (forest, ul, items)
+}
+
+#[test]
+fn transform_forest_001() {
+    let (mut forest, ul, items) = build_list();
+
+    let div = forest.wrap_children(ul, 1..3, "div").unwrap();
+    assert_eq!(render(&forest), "<ul><a></a><div><b></b><c></c></div><d></d></ul>");
+    assert_eq!(forest.get_parent_node(items[1]), Some(div));
+
+    let span = forest.wrap_node(items[1], "span").unwrap();
+    assert_eq!(
+        render(&forest),
+        "<ul><a></a><div><span><b></b></span><c></c></div><d></d></ul>"
+    );
+    assert_eq!(forest.get_first_child_node(div), Some(span));
+
+    assert_eq!(forest.unwrap_node(div), Some("div"));
+    assert_eq!(
+        render(&forest),
+        "<ul><a></a><span><b></b></span><c></c><d></d></ul>"
+    );
+    assert_eq!(forest.get_parent_node(items[2]), Some(ul));
+    assert_eq!(forest.unwrap_node(div), None);
+
+    let body = forest.wrap_node(ul, "body").unwrap();
+    assert_eq!(forest.get_first_root_node(), Some(body));
+    assert_eq!(forest.unwrap_node(body), Some("body"));
+    assert_eq!(forest.get_parent_node(ul), None);
+    assert_eq!(forest.get_first_root_node(), Some(ul));
+    assert_eq!(forest.len(), 6);
+}
+
+#[test]
+fn transform_forest_002() {
+    let (mut forest, ul, items) = build_list();
+
+    forest.wrap_children(ul, .., "all");
+    assert_eq!(render(&forest), "<ul><all><a></a><b></b><c></c><d></d></all></ul>");
+    assert_eq!(forest.unwrap_node(ul), Some("ul"));
+    assert_eq!(render(&forest), "<all><a></a><b></b><c></c><d></d></all>");
+
+    let (mut forest, ul, _) = build_list();
+    forest.wrap_children(ul, 2..2, "empty");
+    forest.wrap_children(ul, 5.., "last");
+    forest.wrap_children(ul, ..=0, "first");
+    assert_eq!(
+        render(&forest),
+        "<ul><first><a></a></first><b></b><empty></empty><c></c><d></d><last></last></ul>"
+    );
+    assert_eq!(forest.unwrap_node(items[3]), Some("d"));
+    assert_eq!(render(&forest).matches("<d>").count(), 0);
+}
+
+#[test]
+fn transform_forest_003() {
+    let (mut forest, ul, _) = build_list();
+    let before = render(&forest);
+
+    assert_eq!(forest.wrap_children(ul, 2..5, "div"), None);
+    let reversed = (Bound::Included(3), Bound::Excluded(2));
+    assert_eq!(forest.wrap_children(ul, reversed, "div"), None);
+    assert_eq!(render(&forest), before);
+    assert_eq!(forest.len(), 5);
+}
+
+#[test]
+fn transform_forest_004() {
+    let (mut forest, ul, _) = build_list();
+    let before = render(&forest);
+
+    assert_eq!(forest.wrap_children(ul, 0..=usize::MAX, "div"), None);
+    let excluded = (Bound::Excluded(usize::MAX), Bound::Unbounded);
+    assert_eq!(forest.wrap_children(ul, excluded, "div"), None);
+    assert_eq!(render(&forest), before);
+}
+
+#[test]
+fn transform_forest_005() {
+    let (mut forest, ul, items) = build_list();
+    forest.remove_node(items[0]);
+
+    assert_eq!(forest.wrap_node(items[0], "span"), None);
+    assert_eq!(forest.wrap_children(items[0], .., "div"), None);
+    assert_eq!(forest.unwrap_node(items[0]), None);
+    assert_eq!(render(&forest), "<ul><b></b><c></c><d></d></ul>");
+    assert!(forest.wrap_children(ul, 1.., "div").is_some());
+    assert_eq!(render(&forest), "<ul><b></b><div><c></c><d></d></div></ul>");
+}